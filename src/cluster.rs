@@ -0,0 +1,66 @@
+//! Keys which type a cluster of several code points.
+//!
+//! Some layouts (such as InScript) have keys typing a conjunct like क्ष in one
+//! keystroke. The output of these keys holds the whole cluster (see `Output`)
+//! so it's never matched by `find_key`, which looks for a single character.
+//! Finding the keys for some text prefers these keys to typing each code point
+//! of the cluster on its own.
+
+use std::iter;
+use ::*;
+
+/// Returns the keys typing a cluster of several code points which `chars`
+/// starts with, the longest first. Each is given with the number of characters
+/// of `chars` it types.
+pub fn prefixes(graph: &Keyboard, chars: &[char]) -> Vec<(Key, usize)> {
+    if chars.len() < 2 {
+        return Vec::new();
+    }
+    let mut result = graph.nodes()
+        .flat_map(|k| iter::once((k, k.value)).chain(iter::once((k, k.shifted))))
+        .filter(|&(_, output)| output.is_cluster())
+        .filter_map(|(k, output)| {
+            let cluster = output.as_str().chars().collect::<Vec<_>>();
+            if chars.starts_with(&cluster) {
+                Some((k, cluster.len()))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    result.sort_by_key(|&(_, len)| std::cmp::Reverse(len));
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cluster::*;
+
+    #[test]
+    fn test_prefixes() {
+        let inscript = generate_inscript();
+        let three = inscript.find_key('3').unwrap();
+        assert_eq!(three.shifted.as_str(), "्र");
+        let chars = "्रा".chars().collect::<Vec<_>>();
+        assert_eq!(prefixes(&inscript, &chars), vec![(three, 2)]);
+        let chars = "क".chars().collect::<Vec<_>>();
+        assert!(prefixes(&inscript, &chars).is_empty());
+        assert!(prefixes(&generate_qwerty_us(), &chars).is_empty());
+    }
+
+    #[test]
+    fn test_outputs() {
+        let seven = generate_inscript().find_key('7').unwrap();
+        assert_eq!(seven.shifted, Output::new("क्ष").unwrap());
+        assert_eq!(format!("{}", seven.shifted), "क्ष");
+        assert_eq!(seven.shifted.as_char(), None);
+        assert!(seven.shifted.is_cluster());
+        assert!(!seven.value.is_cluster());
+        assert!(seven.shifted != 'क');
+        assert_eq!(seven.value, '7');
+        assert_eq!(Output::from('\0'), Output::default());
+        assert!(Output::from('\0').is_empty());
+        assert_eq!(Output::new("too long for a key"), None);
+    }
+}
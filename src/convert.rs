@@ -0,0 +1,197 @@
+//! Converting text typed with the wrong layout active.
+//!
+//! Text typed on one layout while meaning another is the characters on the 
+//! same physical keys, so "ghbdtn" on qwerty is "привет" on ЙЦУКЕН. Keys are
+//! matched by their position in the grid, layer and shift state. Whether the 
+//! converted text is what was meant is guessed from how much it looks like 
+//! words, there's no dictionary so this is only a heuristic.
+
+use std::collections::HashMap;
+use unicode_normalization::UnicodeNormalization;
+use geometry;
+use ::*;
+
+/// Vowels of the latin, greek and cyrillic alphabets without accents
+static VOWELS: &str = "aeiouyαεηιουωаеёиоуыэюяіїє";
+
+/// Least increase in plausibility for text to be flagged as converted
+const MIN_IMPROVEMENT: f64 = 0.2;
+
+/// Text converted between layouts
+#[derive(Debug, PartialEq, Clone)]
+pub struct Conversion {
+    /// Name of the layout the text was typed on
+    pub from: &'static str,
+    /// Name of the layout the text was meant for
+    pub to: &'static str,
+    /// The converted text
+    pub text: String,
+    /// Plausibility of the converted text
+    pub plausibility: f64,
+}
+
+/// Converts text typed on one keyboard to the characters on the same keys of 
+/// another keyboard. Characters which aren't on both keyboards are kept.
+pub fn convert(from: &Keyboard, to: &Keyboard, text: &str) -> String {
+    let from_grid = geometry::grid_positions(from);
+    let to_keys = geometry::grid_positions(to).into_iter()
+                                              .map(|(k, p)| ((p, to.find_layer(k)), k))
+                                              .collect::<HashMap<_, _>>();
+    text.chars()
+        .map(|c| {
+            let key = from.find_key(c);
+            let level = from.find_level(c);
+            match (key, level) {
+                (Some(k), Some(level)) => {
+                    match from_grid.get(&k).and_then(|p| to_keys.get(&(*p, level.layer))) {
+                        Some(t) if level.shifted && t.shifted != '\0' => t.shifted_output(),
+                        Some(t) if !level.shifted && t.value != '\0' => t.value_output(),
+                        _ => c.to_string(),
+                    }
+                },
+                _ => c.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Groups characters into scripts, letters from different scripts are rarely
+/// mixed within a text
+fn script_of(c: char) -> u32 {
+    match c as u32 {
+        0x0000..=0x024F => 0,
+        0x0370..=0x03FF => 1,
+        0x0400..=0x052F => 2,
+        n => 3 + (n >> 7),
+    }
+}
+
+/// Returns true if the letter is a vowel, ignoring case and accents
+fn is_vowel(c: char) -> bool {
+    let base = c.to_lowercase().collect::<String>().nfd().next().unwrap_or(c);
+    VOWELS.contains(base)
+}
+
+/// Rates from 0 to 1 how much text looks like words. Text that is mostly 
+/// letters, all in one script and with a typical share of vowels scores 
+/// highest.
+pub fn plausibility(text: &str) -> f64 {
+    let chars = text.chars().filter(|c| !c.is_whitespace()).collect::<Vec<_>>();
+    let letters = chars.iter().filter(|c| c.is_alphabetic()).cloned().collect::<Vec<_>>();
+    if letters.is_empty() {
+        return 0.0;
+    }
+    let letter_ratio = letters.len() as f64 / chars.len() as f64;
+
+    let mut scripts = HashMap::new();
+    for c in letters.iter() {
+        *scripts.entry(script_of(*c)).or_insert(0) += 1;
+    }
+    let dominant = scripts.iter().max_by_key(|&(_, n)| *n).map(|(s, _)| *s).unwrap_or(0);
+    let consistency = scripts[&dominant] as f64 / letters.len() as f64;
+
+    // Only alphabets with vowels are rated on them
+    let vowel_score = if dominant <= 2 {
+        let vowels = letters.iter().filter(|c| is_vowel(**c)).count() as f64;
+        let ratio = vowels / letters.len() as f64;
+        if ratio < 0.2 {
+            ratio / 0.2
+        } else if ratio > 0.7 {
+            (1.0 - ratio) / 0.3
+        } else {
+            1.0
+        }
+    } else {
+        1.0
+    };
+    letter_ratio * consistency * vowel_score
+}
+
+/// Finds the most plausible reading of text typed on one of the layouts while
+/// meaning another, such as the layouts a user has installed. Returns None if
+/// the text is more plausible as it is.
+pub fn detect(text: &str, layouts: &[(&'static str, &Keyboard)]) -> Option<Conversion> {
+    let original = plausibility(text);
+    let mut best: Option<Conversion> = None;
+    for &(from_name, from) in layouts {
+        let typed = text.chars()
+                        .filter(|c| !c.is_whitespace())
+                        .all(|c| from.find_key(c).is_some());
+        if !typed {
+            continue;
+        }
+        for &(to_name, to) in layouts {
+            if from_name == to_name {
+                continue;
+            }
+            let converted = convert(from, to, text);
+            let score = plausibility(&converted);
+            let better = best.as_ref().map_or(true, |b| score > b.plausibility);
+            if converted != text && score >= original + MIN_IMPROVEMENT && better {
+                best = Some(Conversion {
+                    from: from_name,
+                    to: to_name,
+                    text: converted,
+                    plausibility: score,
+                });
+            }
+        }
+    }
+    best
+}
+
+
+#[cfg(test)]
+mod tests {
+    use convert::*;
+
+    #[test]
+    fn test_convert() {
+        let qwerty = generate_qwerty_us();
+        let russian = generate_russian();
+        assert_eq!(convert(&qwerty, &russian, "ghbdtn"), "привет");
+        assert_eq!(convert(&qwerty, &russian, "Ghbdtn vbh!"), "Привет мир!");
+        assert_eq!(convert(&russian, &qwerty, "привет"), "ghbdtn");
+
+        let dvorak = generate_dvorak();
+        assert_eq!(convert(&dvorak, &qwerty, "hello"), "jdpps");
+        assert_eq!(convert(&qwerty, &dvorak, "jdpps"), "hello");
+        // Keys typing a cluster type all of it
+        assert_eq!(convert(&qwerty, &generate_inscript(), "&"), "क्ष");
+        // Layers are kept
+        let jis = generate_jis();
+        assert_eq!(convert(&jis, &jis, "かt"), "かt");
+
+        // JIS has no key left of 1 but its keys are in the same places
+        assert_eq!(convert(&qwerty, &jis, "qwerty"), "qwerty");
+        assert_eq!(convert(&jis, &qwerty, "asdf"), "asdf");
+        assert_eq!(convert(&qwerty, &jis, "1qaz"), "1qaz");
+        assert_eq!(convert(&jis, &qwerty, convert(&qwerty, &jis, "hello").as_str()), "hello");
+        assert_eq!(convert(&qwerty, &jis, "ghbdtn"), "ghbdtn");
+        assert_eq!(convert(&jis, &russian, "ghbdtn"), "привет");
+    }
+
+    #[test]
+    fn test_plausibility() {
+        assert!(plausibility("привет") > plausibility("ghbdtn"));
+        assert!(plausibility("hello world") > plausibility("jdpps ,soph"));
+        assert!(plausibility("hello мир") < plausibility("hello world"));
+        assert_eq!(plausibility("1234"), 0.0);
+    }
+
+    #[test]
+    fn test_detect() {
+        let layouts = [("QWERTY_US", &*QWERTY_US), ("RUSSIAN", &*RUSSIAN)];
+        let conversion = detect("ghbdtn", &layouts).unwrap();
+        assert_eq!(conversion.text, "привет");
+        assert_eq!((conversion.from, conversion.to), ("QWERTY_US", "RUSSIAN"));
+        assert!(detect("hello", &layouts).is_none());
+        assert!(detect("привет", &layouts).is_none());
+
+        let layouts = [("QWERTY_US", &*QWERTY_US), ("DVORAK", &*DVORAK)];
+        let typed = convert(&DVORAK, &QWERTY_US, "hello world");
+        assert_eq!(detect(&typed, &layouts).unwrap().text, "hello world");
+
+        assert!(LAYOUTS.iter().any(|&(name, _)| name == "RUSSIAN"));
+    }
+}
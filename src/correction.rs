@@ -0,0 +1,144 @@
+//! Typo tolerant password checking.
+//!
+//! Rather than rejecting a password with a common typo, a site can also check
+//! a few corrections of the submitted password against the stored hash. Only
+//! corrections of mistakes which are common and keep most of the password
+//! intact are tried, so the password is barely easier to guess.
+
+use typo::TypoModel;
+use ::*;
+
+/// Controls which corrections of a submitted password are tried
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CorrectionPolicy {
+    /// Invert the case of every letter, as if typed with caps lock on
+    pub caps_lock: bool,
+    /// Invert the case of the first letter, as often done by phones
+    pub first_letter_case: bool,
+    /// Replace the last character with one on a neighbouring key
+    pub last_key_substitution: bool,
+    /// Remove an extra character typed at the end
+    pub trailing_character: bool,
+    /// Most corrections returned
+    pub max_candidates: usize,
+}
+
+impl Default for CorrectionPolicy {
+    fn default() -> CorrectionPolicy {
+        CorrectionPolicy {
+            caps_lock: true,
+            first_letter_case: true,
+            last_key_substitution: true,
+            trailing_character: false,
+            max_candidates: 16,
+        }
+    }
+}
+
+/// Returns the corrections of a submitted password allowed by the policy, in
+/// order of how common the mistake is. The submitted password isn't included.
+pub fn corrections(keyboard: &Keyboard,
+                   submitted: &str,
+                   policy: &CorrectionPolicy) -> Vec<String> {
+    let model = TypoModel::new(keyboard);
+    let chars = submitted.chars().collect::<Vec<_>>();
+    let typed = chars.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+    let invert = |c: char| if c.is_alphabetic() {
+        model.shift_slip(c).unwrap_or_else(|| c.to_string())
+    } else {
+        c.to_string()
+    };
+    let mut candidates: Vec<Vec<String>> = Vec::new();
+
+    if policy.caps_lock {
+        candidates.push(chars.iter().map(|c| invert(*c)).collect());
+    }
+    if policy.first_letter_case && !chars.is_empty() {
+        let mut corrected = typed.clone();
+        corrected[0] = invert(chars[0]);
+        candidates.push(corrected);
+    }
+    if policy.trailing_character && !chars.is_empty() {
+        candidates.push(typed[..typed.len() - 1].to_vec());
+    }
+    if policy.last_key_substitution {
+        if let Some(&last) = chars.last() {
+            for n in model.neighbours(last) {
+                let mut corrected = typed.clone();
+                *corrected.last_mut().unwrap() = n;
+                candidates.push(corrected);
+            }
+        }
+    }
+
+    let mut result: Vec<String> = Vec::new();
+    for c in candidates {
+        let c = c.concat();
+        if c != submitted && !result.contains(&c) {
+            result.push(c);
+        }
+    }
+    result.truncate(policy.max_candidates);
+    result
+}
+
+/// Checks the submitted password and then its corrections with `check`, 
+/// which compares a password against the stored hash. Returns the password
+/// which matched.
+pub fn verify<F>(keyboard: &Keyboard,
+                 submitted: &str,
+                 policy: &CorrectionPolicy,
+                 check: F) -> Option<String> 
+    where F: Fn(&str) -> bool
+{
+    if check(submitted) {
+        return Some(submitted.to_string());
+    }
+    corrections(keyboard, submitted, policy).into_iter().find(|c| check(c))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use correction::*;
+
+    #[test]
+    fn test_corrections() {
+        let qwerty = generate_qwerty_us();
+        let policy = CorrectionPolicy::default();
+        let candidates = corrections(&qwerty, "pASSWORD1", &policy);
+        assert_eq!(candidates[0], "Password1");
+        assert_eq!(candidates[1], "PASSWORD1");
+        // 1 is next to ` 2 and q
+        assert_eq!(candidates.len(), 5);
+        assert!(candidates.contains(&"pASSWORD2".to_string()));
+        assert!(candidates.contains(&"pASSWORDq".to_string()));
+        assert!(!candidates.contains(&"pASSWORD1".to_string()));
+
+        let strict = CorrectionPolicy {
+            last_key_substitution: false,
+            trailing_character: true,
+            ..CorrectionPolicy::default()
+        };
+        assert_eq!(corrections(&qwerty, "Secret1", &strict),
+                   vec!["sECRET1".to_string(), "secret1".to_string(), "Secret".to_string()]);
+
+        let bounded = CorrectionPolicy {
+            max_candidates: 2,
+            ..CorrectionPolicy::default()
+        };
+        assert_eq!(corrections(&qwerty, "secret", &bounded).len(), 2);
+        assert!(corrections(&qwerty, "", &policy).is_empty());
+    }
+
+    #[test]
+    fn test_verify() {
+        let qwerty = generate_qwerty_us();
+        let policy = CorrectionPolicy::default();
+        let stored = |p: &str| p == "Tr0ub4dor";
+        assert_eq!(verify(&qwerty, "Tr0ub4dor", &policy, stored), Some("Tr0ub4dor".to_string()));
+        assert_eq!(verify(&qwerty, "tR0UB4DOR", &policy, stored), Some("Tr0ub4dor".to_string()));
+        assert_eq!(verify(&qwerty, "Tr0ub4dot", &policy, stored), Some("Tr0ub4dor".to_string()));
+        assert_eq!(verify(&qwerty, "Tr0ub4dop", &policy, stored), None);
+    }
+}
@@ -0,0 +1,61 @@
+//! Accented letters typed with dead keys.
+//!
+//! A dead key types nothing by itself, instead it adds its accent to the next
+//! letter typed. Keyboards store a dead key by the spacing form of its accent
+//! (´ for the acute accent) so an accented letter without a key of its own is
+//! found as the dead key followed by the letter.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Combining accents and the spacing characters dead keys adding them are 
+/// stored as.
+static DEAD_KEYS: &[(char, &str)] = &[
+    ('\u{0300}', "`"),
+    ('\u{0301}', "´΄"),
+    ('\u{0302}', "^"),
+    ('\u{0303}', "~"),
+    ('\u{0308}', "¨"),
+];
+
+/// Returns the spacing characters a dead key adding the combining accent may
+/// be stored as. The string is empty if the accent isn't typed with a dead
+/// key.
+pub fn dead_keys(accent: char) -> &'static str {
+    DEAD_KEYS.iter()
+             .find(|&&(a, _)| a == accent)
+             .map_or("", |&(_, keys)| keys)
+}
+
+/// Splits an accented letter into a dead key and the letter typed after it.
+/// There's a pair for each way the dead key may be stored, the vector is 
+/// empty if the character isn't a letter with a single dead key accent.
+pub fn keystrokes(c: char) -> Vec<(char, char)> {
+    let decomposed = c.nfd().collect::<Vec<_>>();
+    match decomposed.as_slice() {
+        [letter, accent] if letter.is_alphabetic() => {
+            dead_keys(*accent).chars().map(|k| (k, *letter)).collect()
+        },
+        _ => Vec::new(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use deadkey::*;
+
+    #[test]
+    fn test_keystrokes() {
+        assert_eq!(keystrokes('é'), vec![('´', 'e'), ('΄', 'e')]);
+        assert_eq!(keystrokes('Ü'), vec![('¨', 'U')]);
+        assert_eq!(keystrokes('ã'), vec![('~', 'a')]);
+        assert_eq!(keystrokes('ά'), vec![('´', 'α'), ('΄', 'α')]);
+        // Cedilla and ring aren't dead keys, ǘ has two accents
+        assert!(keystrokes('ç').is_empty());
+        assert!(keystrokes('å').is_empty());
+        assert!(keystrokes('ǘ').is_empty());
+        assert!(keystrokes('e').is_empty());
+        assert_eq!(dead_keys('\u{0302}'), "^");
+        assert_eq!(dead_keys('\u{0327}'), "");
+    }
+}
@@ -0,0 +1,223 @@
+//! Edit distances weighted by keyboard layout.
+//!
+//! Typing mistakes mostly hit a key next to the one intended or the other 
+//! level of the same key, so these substitutions cost less than replacing a
+//! character with one across the keyboard. Keys typed by different hands are
+//! easily pressed out of order so transposing them costs less too.
+
+use std::collections::HashMap;
+use geometry;
+use ::*;
+
+/// Costs of each edit used by `KeyboardDistance`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct EditCosts {
+    /// Inserting a character
+    pub insertion: f64,
+    /// Deleting a character
+    pub deletion: f64,
+    /// Substituting a character with one on a key that isn't adjacent
+    pub substitution: f64,
+    /// Substituting a character with one on an adjacent key
+    pub adjacent_substitution: f64,
+    /// Substituting a character with another on the same key, i.e. a shift
+    /// slip or a character on another layer
+    pub same_key_substitution: f64,
+    /// Swapping two characters typed by the same hand
+    pub transposition: f64,
+    /// Swapping two characters typed by different hands
+    pub opposite_hand_transposition: f64,
+    /// Whether substitutions between keys which aren't adjacent are scaled by
+    /// the distance between them, up to the cost of any substitution
+    pub geometric: bool,
+}
+
+impl Default for EditCosts {
+    fn default() -> EditCosts {
+        EditCosts {
+            insertion: 1.0,
+            deletion: 1.0,
+            substitution: 1.0,
+            adjacent_substitution: 0.5,
+            same_key_substitution: 0.5,
+            transposition: 1.0,
+            opposite_hand_transposition: 0.5,
+            geometric: false,
+        }
+    }
+}
+
+/// Returns the key on the base layer of the same physical key as a key. Long
+/// pressed keys are on the physical key pressed.
+fn base_key(keyboard: &Keyboard, key: Key) -> Key {
+    let mut current = mobile::pressed_key(keyboard, key).unwrap_or(key);
+    while let Some(lower) = lower_layer(keyboard, current) {
+        current = lower;
+    }
+    current
+}
+
+/// Edit distance between strings typed on a keyboard
+pub struct KeyboardDistance<'a> {
+    keyboard: &'a Keyboard,
+    costs: EditCosts,
+    grid: HashMap<Key, (i32, i32)>,
+    positions: HashMap<Key, (f32, f32)>,
+    /// Key typing each character, as found by `find_key`
+    keys: HashMap<char, Key>,
+    /// Key on the base layer of the same physical key as each key
+    bases: HashMap<Key, Key>,
+}
+
+impl<'a> KeyboardDistance<'a> {
+    /// Creates the distance for a keyboard with the default costs
+    pub fn new(keyboard: &'a Keyboard) -> KeyboardDistance<'a> {
+        KeyboardDistance::with_costs(keyboard, EditCosts::default())
+    }
+
+    /// Creates the distance for a keyboard with the given costs
+    pub fn with_costs(keyboard: &'a Keyboard, costs: EditCosts) -> KeyboardDistance<'a> {
+        let mut keys = HashMap::new();
+        for k in keyboard.nodes() {
+            for c in [k.value, k.shifted].iter().filter_map(|o| o.as_char()) {
+                keys.entry(c).or_insert(k);
+            }
+        }
+        let bases = keyboard.nodes()
+                            .map(|k| (k, base_key(keyboard, k)))
+                            .collect();
+        KeyboardDistance {
+            keyboard,
+            costs,
+            grid: geometry::grid_positions(keyboard),
+            positions: geometry::positions(keyboard),
+            keys,
+            bases,
+        }
+    }
+
+    /// Returns the costs used
+    pub fn costs(&self) -> &EditCosts {
+        &self.costs
+    }
+
+    /// Returns the cost of typing `b` in place of `a`. Characters which aren't
+    /// on the keyboard cost a full substitution.
+    pub fn substitution_cost(&self, a: char, b: char) -> f64 {
+        if a == b {
+            return 0.0;
+        }
+        let (ka, kb) = match (self.keys.get(&a), self.keys.get(&b)) {
+            (Some(ka), Some(kb)) => (*ka, *kb),
+            _ => return self.costs.substitution,
+        };
+        if self.bases[&ka] == self.bases[&kb] {
+            self.costs.same_key_substitution
+        } else if self.keyboard.edge_weight(ka, kb).is_some_and(|e| !e.is_same_key()) {
+            self.costs.adjacent_substitution
+        } else if self.costs.geometric {
+            geometry::distance(&self.positions, self.bases[&ka], self.bases[&kb])
+                .map_or(self.costs.substitution, |d| {
+                    (self.costs.adjacent_substitution * d as f64).min(self.costs.substitution)
+                })
+        } else {
+            self.costs.substitution
+        }
+    }
+
+    /// Returns the cost of typing `b` then `a` in place of `a` then `b`
+    pub fn transposition_cost(&self, a: char, b: char) -> f64 {
+        let hand = |c| self.keys.get(&c).and_then(|k| geometry::hand(&self.grid, self.bases[k]));
+        match (hand(a), hand(b)) {
+            (Some(ha), Some(hb)) if ha != hb => self.costs.opposite_hand_transposition,
+            _ => self.costs.transposition,
+        }
+    }
+
+    /// Weighted Levenshtein distance, the cheapest sequence of insertions,
+    /// deletions and substitutions turning `a` into `b`.
+    pub fn levenshtein(&self, a: &str, b: &str) -> f64 {
+        self.edit_distance(a, b, false)
+    }
+
+    /// Weighted Damerau-Levenshtein distance (optimal string alignment), 
+    /// which also allows transposing adjacent characters.
+    pub fn damerau(&self, a: &str, b: &str) -> f64 {
+        self.edit_distance(a, b, true)
+    }
+
+    fn edit_distance(&self, a: &str, b: &str, transpositions: bool) -> f64 {
+        let a = a.chars().collect::<Vec<_>>();
+        let b = b.chars().collect::<Vec<_>>();
+        let mut table = vec![vec![0.0; b.len() + 1]; a.len() + 1];
+        for i in 1..=a.len() {
+            table[i][0] = table[i - 1][0] + self.costs.deletion;
+        }
+        for j in 1..=b.len() {
+            table[0][j] = table[0][j - 1] + self.costs.insertion;
+        }
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                let mut best = (table[i - 1][j] + self.costs.deletion)
+                    .min(table[i][j - 1] + self.costs.insertion)
+                    .min(table[i - 1][j - 1] + self.substitution_cost(a[i - 1], b[j - 1]));
+                if transpositions && i > 1 && j > 1 &&
+                   a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] && a[i - 1] != a[i - 2] {
+                    best = best.min(table[i - 2][j - 2] + self.transposition_cost(a[i - 2], a[i - 1]));
+                }
+                table[i][j] = best;
+            }
+        }
+        table[a.len()][b.len()]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use distance::*;
+
+    #[test]
+    fn test_substitution() {
+        let qwerty = generate_qwerty_us();
+        let distance = KeyboardDistance::new(&qwerty);
+        assert_eq!(distance.substitution_cost('g', 'g'), 0.0);
+        assert!(distance.substitution_cost('g', 'h') < distance.substitution_cost('g', 'p'));
+        assert_eq!(distance.substitution_cost('a', 'A'), 0.5);
+        assert_eq!(distance.substitution_cost('1', '!'), 0.5);
+        assert_eq!(distance.substitution_cost('g', 'é'), 1.0);
+
+        // Keys on other layers of the same physical key
+        let jis = generate_jis();
+        let distance = KeyboardDistance::new(&jis);
+        assert_eq!(distance.substitution_cost('t', 'か'), 0.5);
+        assert_eq!(distance.substitution_cost('y', 'か'), 1.0);
+
+        let geometric = KeyboardDistance::with_costs(&qwerty, EditCosts {
+            adjacent_substitution: 0.25,
+            geometric: true,
+            ..EditCosts::default()
+        });
+        let near = geometric.substitution_cost('g', 'j');
+        let far = geometric.substitution_cost('g', 'p');
+        assert!(geometric.substitution_cost('g', 'h') < near);
+        assert!(near < far);
+        assert_eq!(far, 1.0);
+    }
+
+    #[test]
+    fn test_distance() {
+        let qwerty = generate_qwerty_us();
+        let distance = KeyboardDistance::new(&qwerty);
+        assert_eq!(distance.levenshtein("", "abc"), 3.0);
+        assert_eq!(distance.levenshtein("password", "password"), 0.0);
+        assert_eq!(distance.levenshtein("password", "passwprd"), 0.5);
+        assert_eq!(distance.levenshtein("password", "passwzrd"), 1.0);
+        assert_eq!(distance.levenshtein("password", "Password"), 0.5);
+
+        // e and h are typed by different hands, r and e aren't
+        assert_eq!(distance.damerau("the", "teh"), 0.5);
+        assert_eq!(distance.damerau("there", "three"), 1.0);
+        assert_eq!(distance.levenshtein("the", "teh"), 2.0);
+    }
+}
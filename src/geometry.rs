@@ -0,0 +1,300 @@
+//! Positions of keys recovered from the directions of the edges between them.
+//!
+//! Edges give the position of a neighbour relative to a key in rows and 
+//! columns of the keyboard string the graph was generated from, so following
+//! them from any key lays the keyboard back out. Keys on other layers are at
+//! the position of the key below them. Touchscreen rows can be offset by half
+//! a key, which the edges don't say, so it's worked out from whether any key
+//! in a row is directly above one in the next.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use petgraph::Direction::{Incoming, Outgoing};
+use ::*;
+
+/// Returns the column and row of every key, as in the keyboard string the
+/// graph was generated from. Rows are counted from the top row of each
+/// connected group of keys. On slanted keyboards the rows below the top one
+/// start with a void offset, so columns are counted from one left of the first
+/// key of the second row. This keeps keys in the same place on layouts with a
+/// void key at the start of the top row (i.e. JIS). Otherwise columns are
+/// counted from the leftmost key, keys in rows offset by half a key (i.e. on
+/// touchscreens) are in the column of the half key they start in. Keys typed
+/// by long pressing another key have no position of their own and are left
+/// out.
+pub fn grid_positions(graph: &Keyboard) -> HashMap<Key, (i32, i32)> {
+    layout(graph).into_iter()
+                 .map(|(k, (x, y))| (k, (x.floor() as i32, y)))
+                 .collect()
+}
+
+/// Lays out the keys of every connected group by column and row. Each layer
+/// is laid out from the edges between its own keys and then moved to where
+/// most of the same key edges into it put it, as keys on touchscreen pages
+/// are only linked to the nearest key on the page below.
+fn layout(graph: &Keyboard) -> HashMap<Key, (f32, i32)> {
+    let slanted = !is_aligned(graph);
+    let mut layers: Vec<HashMap<Key, (f32, i32)>> = Vec::new();
+    let mut layer_of = HashMap::new();
+    for start in graph.nodes() {
+        if layer_of.contains_key(&start) || mobile::is_long_press(graph, start) {
+            continue;
+        }
+        let layer = layout_layer(graph, start, slanted);
+        for k in layer.keys() {
+            layer_of.insert(*k, layers.len());
+        }
+        layers.push(layer);
+    }
+
+    // Layers with nothing below them start their own group, the others are
+    // moved into place once a layer below them is placed
+    let mut placed: Vec<Option<(usize, (f32, i32))>> = vec![None; layers.len()];
+    for (i, layer) in layers.iter().enumerate() {
+        let linked = layer.keys().any(|k| {
+            lower_layer(graph, *k).is_some_and(|n| layer_of[&n] != i)
+        });
+        if !linked {
+            placed[i] = Some((i, (0.0, 0)));
+        }
+    }
+    while placed.iter().any(|p| p.is_none()) {
+        let mut progress = false;
+        for i in 0..layers.len() {
+            if placed[i].is_some() {
+                continue;
+            }
+            let mut counts: Vec<((i32, i32), usize, usize)> = Vec::new();
+            for (k, &(x, y)) in &layers[i] {
+                let n = match lower_layer(graph, *k) {
+                    Some(n) => n,
+                    None => continue,
+                };
+                let (group, (dx, dy)) = match placed[layer_of[&n]] {
+                    Some(p) => p,
+                    None => continue,
+                };
+                let (nx, ny) = layers[layer_of[&n]][&n];
+                // Offsets are whole or half keys so count them in halves
+                let offset = (((nx + dx - x) * 2.0).round() as i32, ny + dy - y);
+                match counts.iter_mut().find(|c| c.0 == offset) {
+                    Some(c) => c.2 += 1,
+                    None => counts.push((offset, group, 1)),
+                }
+            }
+            let best = counts.iter().fold(None, |best: Option<&((i32, i32), usize, usize)>, c| {
+                match best {
+                    Some(b) if b.2 >= c.2 => best,
+                    _ => Some(c),
+                }
+            });
+            if let Some(&((x, y), group, _)) = best {
+                placed[i] = Some((group, (x as f32 / 2.0, y)));
+                progress = true;
+            }
+        }
+        if !progress {
+            // Layers only linked to each other start a group from the first
+            let i = placed.iter().position(|p| p.is_none()).unwrap();
+            placed[i] = Some((i, (0.0, 0)));
+        }
+    }
+
+    let mut groups: HashMap<usize, HashMap<Key, (f32, i32)>> = HashMap::new();
+    for (layer, p) in layers.into_iter().zip(placed) {
+        let (group, (dx, dy)) = p.unwrap();
+        groups.entry(group)
+              .or_default()
+              .extend(layer.into_iter().map(|(k, (x, y))| (k, (x + dx, y + dy))));
+    }
+
+    let mut result = HashMap::new();
+    for group in groups.values() {
+        let min_x = group.values().map(|p| p.0).fold(f32::INFINITY, f32::min);
+        let min_y = group.values().map(|p| p.1).min().unwrap_or(0);
+        let second_row = group.values()
+                              .filter(|p| p.1 == min_y + 1)
+                              .map(|p| p.0)
+                              .fold(None, |m: Option<f32>, x| Some(m.map_or(x, |m| m.min(x))));
+        let min_x = match second_row {
+            Some(x) if slanted => x - 1.0,
+            _ => min_x,
+        };
+        for (k, &(x, y)) in group {
+            result.insert(*k, (x - min_x, y - min_y));
+        }
+    }
+    result
+}
+
+/// Lays out the keys on the same layer as a key from the edges between them.
+/// On aligned keyboards neighbouring rows with no key directly above another
+/// are offset by half a key, so diagonal edges between them are half a key
+/// across.
+fn layout_layer(graph: &Keyboard, start: Key, slanted: bool) -> HashMap<Key, (f32, i32)> {
+    let neighbours = |k: Key| {
+        let outgoing = graph.neighbors_directed(k, Outgoing)
+                            .filter_map(move |n| graph.edge_weight(k, n).map(|e| (n, *e, 1)));
+        let incoming = graph.neighbors_directed(k, Incoming)
+                            .filter_map(move |n| graph.edge_weight(n, k).map(|e| (n, *e, -1)));
+        outgoing.chain(incoming)
+                .filter(|&(_, e, _)| !e.is_same_key())
+                .collect::<Vec<_>>()
+    };
+
+    let mut rows = HashMap::new();
+    let mut queue = VecDeque::new();
+    rows.insert(start, 0);
+    queue.push_back(start);
+    while let Some(k) = queue.pop_front() {
+        let y = rows[&k];
+        for (n, e, sign) in neighbours(k) {
+            if rows.contains_key(&n) {
+                continue;
+            }
+            rows.insert(n, y + sign * e.vertical as i32);
+            queue.push_back(n);
+        }
+    }
+
+    // Neighbouring rows line up if any key is directly above another
+    let mut whole_rows = HashSet::new();
+    for &k in rows.keys() {
+        for (n, e, _) in neighbours(k) {
+            if e.horizontal == Direction::Same && e.vertical != Direction::Same {
+                whole_rows.insert(rows[&k].min(rows[&n]));
+            }
+        }
+    }
+
+    let mut result = HashMap::new();
+    result.insert(start, (0.0, 0));
+    queue.push_back(start);
+    while let Some(k) = queue.pop_front() {
+        let (x, y) = result[&k];
+        for (n, e, sign) in neighbours(k) {
+            if result.contains_key(&n) {
+                continue;
+            }
+            let ny = rows[&n];
+            let width = if slanted || ny == y || whole_rows.contains(&y.min(ny)) {
+                1.0
+            } else {
+                0.5
+            };
+            result.insert(n, (x + (sign * e.horizontal as i32) as f32 * width, ny));
+            queue.push_back(n);
+        }
+    }
+    result
+}
+
+/// Returns true if the keys are aligned in a grid (i.e. numpads) rather than 
+/// slanted. Only aligned keys have neighbours diagonally above and to the left.
+pub fn is_aligned(graph: &Keyboard) -> bool {
+    graph.all_edges().any(|(_, _, e)| {
+        e.horizontal == Direction::Previous && e.vertical == Direction::Previous
+    })
+}
+
+/// Returns the position of the centre of every key measured in key widths.
+/// On slanted keyboards each row is half a key further right than the row 
+/// above it. Long pressed keys are left out as in `grid_positions`.
+pub fn positions(graph: &Keyboard) -> HashMap<Key, (f32, f32)> {
+    let slant = if is_aligned(graph) { 0.0 } else { 0.5 };
+    layout(graph).into_iter()
+                 .map(|(k, (x, y))| (k, (x + slant * y as f32, y as f32)))
+                 .collect()
+}
+
+/// Hands used to type keys on a keyboard
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// Returns the hand a key is typed with by touch typists, keys in the first 
+/// six columns of the grid (up to 5, t, g and b on qwerty) are typed with the
+/// left hand. Returns None if the key isn't in the positions.
+pub fn hand(grid: &HashMap<Key, (i32, i32)>, key: Key) -> Option<Hand> {
+    grid.get(&key).map(|&(x, _)| if x <= 5 { Hand::Left } else { Hand::Right })
+}
+
+/// Returns the distance between the centres of two keys in key widths, or 
+/// None if either key isn't in the positions.
+pub fn distance(positions: &HashMap<Key, (f32, f32)>, a: Key, b: Key) -> Option<f32> {
+    match (positions.get(&a), positions.get(&b)) {
+        (Some(&(ax, ay)), Some(&(bx, by))) => Some(((ax - bx).powi(2) + (ay - by).powi(2)).sqrt()),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use geometry::*;
+
+    #[test]
+    fn test_positions() {
+        let numpad = generate_standard_numpad();
+        let grid = grid_positions(&numpad);
+        let find = |c| grid[&numpad.find_key(c).unwrap()];
+        assert_eq!(find('7'), (0, 1));
+        assert_eq!(find('3'), (2, 3));
+        assert_eq!(find('0'), (1, 4));
+        assert!(is_aligned(&numpad));
+
+        let qwerty = generate_qwerty_us();
+        assert!(!is_aligned(&qwerty));
+        let positions = positions(&qwerty);
+        let q = qwerty.find_key('q').unwrap();
+        let a = qwerty.find_key('a').unwrap();
+        let w = qwerty.find_key('w').unwrap();
+        let p = qwerty.find_key('p').unwrap();
+        assert_eq!(distance(&positions, q, w), Some(1.0));
+        assert_eq!(distance(&positions, q, p), Some(9.0));
+        assert!(distance(&positions, q, a).unwrap() < 1.2);
+
+        let grid = grid_positions(&qwerty);
+        let hand = |c| hand(&grid, qwerty.find_key(c).unwrap());
+        assert_eq!(hand('t'), Some(Hand::Left));
+        assert_eq!(hand('b'), Some(Hand::Left));
+        assert_eq!(hand('y'), Some(Hand::Right));
+        assert_eq!(hand('6'), Some(Hand::Right));
+
+        // Layers share the position of the key below
+        let jis = generate_jis();
+        let grid = grid_positions(&jis);
+        assert_eq!(grid[&jis.find_key('か').unwrap()], grid[&jis.find_key('t').unwrap()]);
+
+        // The void key left of 1 on JIS still takes up a column
+        assert_eq!(grid[&jis.find_key('1').unwrap()], (1, 0));
+        assert_eq!(grid[&jis.find_key('q').unwrap()], (1, 1));
+        let qwerty_grid = grid_positions(&qwerty);
+        assert_eq!(qwerty_grid[&qwerty.find_key('1').unwrap()], (1, 0));
+        let jis_hand = |c| geometry::hand(&grid, jis.find_key(c).unwrap());
+        assert_eq!(jis_hand('t'), Some(Hand::Left));
+        assert_eq!(jis_hand('y'), Some(Hand::Right));
+    }
+
+    #[test]
+    fn test_offset_rows() {
+        // Touchscreen rows are offset by half a key and keys on the symbols
+        // page are only linked to the nearest letter below
+        let ios = generate_ios();
+        let positions = positions(&ios);
+        let find = |c| positions[&ios.find_key(c).unwrap()];
+        assert_eq!(find('q'), (0.0, 0.0));
+        assert_eq!(find('a'), (0.5, 1.0));
+        assert_eq!(find('z'), (1.5, 2.0));
+        assert_eq!(find('1'), (0.0, 0.0));
+        assert_eq!(find('-'), (0.0, 1.0));
+        assert_eq!(find('.'), (1.5, 2.0));
+
+        let grid = grid_positions(&ios);
+        let find = |c| grid[&ios.find_key(c).unwrap()];
+        assert_eq!(find('2'), (1, 0));
+        assert_eq!(find('3'), (2, 0));
+        assert_eq!(find('h'), (5, 1));
+    }
+}
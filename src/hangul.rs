@@ -0,0 +1,143 @@
+//! Conversion of hangul into the jamo keys pressed to type it.
+//!
+//! Korean text is made of syllable blocks (한) composed by the input method 
+//! from a sequence of jamo keystrokes (ㅎ ㅏ ㄴ). Compound vowels and final
+//! consonant clusters have no key of their own so are split into the jamo that
+//! build them.
+
+/// First code point of the precomposed hangul syllables block
+const SYLLABLE_BASE: u32 = 0xAC00;
+/// Number of precomposed hangul syllables
+const SYLLABLE_COUNT: u32 = 11172;
+/// Number of syllables sharing a leading consonant
+const LEAD_STRIDE: u32 = 588;
+/// Number of syllables sharing a leading consonant and vowel
+const VOWEL_STRIDE: u32 = 28;
+
+/// Leading consonants in syllable order
+static LEADS: &[char] = &[
+    'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ',
+    'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ'
+];
+
+/// Vowels in syllable order
+static VOWELS: &[char] = &[
+    'ㅏ', 'ㅐ', 'ㅑ', 'ㅒ', 'ㅓ', 'ㅔ', 'ㅕ', 'ㅖ', 'ㅗ', 'ㅘ', 'ㅙ', 'ㅚ', 'ㅛ', 'ㅜ',
+    'ㅝ', 'ㅞ', 'ㅟ', 'ㅠ', 'ㅡ', 'ㅢ', 'ㅣ'
+];
+
+/// Final consonants in syllable order, the first is no final consonant
+static TAILS: &[char] = &[
+    '\0', 'ㄱ', 'ㄲ', 'ㄳ', 'ㄴ', 'ㄵ', 'ㄶ', 'ㄷ', 'ㄹ', 'ㄺ', 'ㄻ', 'ㄼ', 'ㄽ', 'ㄾ',
+    'ㄿ', 'ㅀ', 'ㅁ', 'ㅂ', 'ㅄ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ'
+];
+
+/// Compound jamo and the jamo typed to build them
+static COMPOUNDS: &[(char, &str)] = &[
+    ('ㅘ', "ㅗㅏ"),
+    ('ㅙ', "ㅗㅐ"),
+    ('ㅚ', "ㅗㅣ"),
+    ('ㅝ', "ㅜㅓ"),
+    ('ㅞ', "ㅜㅔ"),
+    ('ㅟ', "ㅜㅣ"),
+    ('ㅢ', "ㅡㅣ"),
+    ('ㄳ', "ㄱㅅ"),
+    ('ㄵ', "ㄴㅈ"),
+    ('ㄶ', "ㄴㅎ"),
+    ('ㄺ', "ㄹㄱ"),
+    ('ㄻ', "ㄹㅁ"),
+    ('ㄼ', "ㄹㅂ"),
+    ('ㄽ', "ㄹㅅ"),
+    ('ㄾ', "ㄹㅌ"),
+    ('ㄿ', "ㄹㅍ"),
+    ('ㅀ', "ㄹㅎ"),
+    ('ㅄ', "ㅂㅅ"),
+];
+
+/// Returns true if the character is a precomposed hangul syllable
+pub fn is_syllable(c: char) -> bool {
+    let code = c as u32;
+    (SYLLABLE_BASE..SYLLABLE_BASE + SYLLABLE_COUNT).contains(&code)
+}
+
+/// Splits a precomposed syllable into its leading consonant, vowel and 
+/// optional final consonant. Returns None if `c` isn't a hangul syllable.
+pub fn decompose(c: char) -> Option<(char, char, Option<char>)> {
+    if !is_syllable(c) {
+        return None;
+    }
+    let index = c as u32 - SYLLABLE_BASE;
+    let lead = LEADS[(index / LEAD_STRIDE) as usize];
+    let vowel = VOWELS[((index % LEAD_STRIDE) / VOWEL_STRIDE) as usize];
+    let tail = match index % VOWEL_STRIDE {
+        0 => None,
+        t => Some(TAILS[t as usize]),
+    };
+    Some((lead, vowel, tail))
+}
+
+/// Maps a conjoining jamo (as found in decomposed text) to the compatibility
+/// jamo printed on the keyboard. Other characters are returned unchanged.
+fn to_compatibility(c: char) -> char {
+    match c as u32 {
+        code @ 0x1100..=0x1112 => LEADS[(code - 0x1100) as usize],
+        code @ 0x1161..=0x1175 => VOWELS[(code - 0x1161) as usize],
+        code @ 0x11A8..=0x11C2 => TAILS[(code - 0x11A7) as usize],
+        _ => c,
+    }
+}
+
+/// Splits a jamo into the jamo typed to build it
+fn split_compound(c: char, result: &mut Vec<char>) {
+    match COMPOUNDS.iter().find(|&&(compound, _)| compound == c) {
+        Some(&(_, parts)) => result.extend(parts.chars()),
+        None => result.push(c),
+    }
+}
+
+/// Returns the jamo typed on a dubeolsik keyboard to produce `c`. Characters
+/// which aren't hangul are returned unchanged.
+pub fn keystrokes(c: char) -> Vec<char> {
+    let mut result = Vec::new();
+    match decompose(c) {
+        Some((lead, vowel, tail)) => {
+            result.push(lead);
+            split_compound(vowel, &mut result);
+            if let Some(tail) = tail {
+                split_compound(tail, &mut result);
+            }
+        },
+        None => split_compound(to_compatibility(c), &mut result),
+    }
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hangul::*;
+
+    #[test]
+    fn test_decompose() {
+        assert_eq!(decompose('한'), Some(('ㅎ', 'ㅏ', Some('ㄴ'))));
+        assert_eq!(decompose('가'), Some(('ㄱ', 'ㅏ', None)));
+        assert_eq!(decompose('힣'), Some(('ㅎ', 'ㅣ', Some('ㅎ'))));
+        assert_eq!(decompose('a'), None);
+        assert_eq!(decompose('ㄱ'), None);
+    }
+
+    #[test]
+    fn test_keystrokes() {
+        assert_eq!(keystrokes('한'), vec!['ㅎ', 'ㅏ', 'ㄴ']);
+        // Compound vowel and final consonant cluster
+        assert_eq!(keystrokes('괜'), vec!['ㄱ', 'ㅗ', 'ㅐ', 'ㄴ']);
+        assert_eq!(keystrokes('닭'), vec!['ㄷ', 'ㅏ', 'ㄹ', 'ㄱ']);
+        // Doubled consonants have their own (shifted) key
+        assert_eq!(keystrokes('까'), vec!['ㄲ', 'ㅏ']);
+        assert_eq!(keystrokes('ㅢ'), vec!['ㅡ', 'ㅣ']);
+        // Conjoining jamo from decomposed text
+        assert_eq!(keystrokes('\u{1112}'), vec!['ㅎ']);
+        assert_eq!(keystrokes('\u{11AB}'), vec!['ㄴ']);
+        assert_eq!(keystrokes('a'), vec!['a']);
+    }
+}
@@ -0,0 +1,271 @@
+//! Guessing the layout a text sample or keystroke log was typed on.
+//!
+//! A layout that can't type some of the characters is unlikely to be the one
+//! used, so layouts are ranked by the share of the text they can type. A log
+//! recording whether shift was held also rules out layouts where a character
+//! needs the other shift state, like # which is shifted on US keyboards but
+//! not on UK ones. Keyboard walks, like "qwerty" or "aoeu", tell layouts apart
+//! as the keys are only in a line on some of them. Some layouts put keys
+//! common in ordinary text in a line too, so walks only count when there are
+//! clearly more than the layout gives ordinary text.
+
+use std::ptr;
+use ::*;
+
+/// Weight of the share of keystrokes in walks in the score
+const WALK_WEIGHT: f64 = 1.0;
+
+/// Raising the coverage and shift agreement to a power makes missing
+/// characters and the wrong shift state costly
+const COVERAGE_EXPONENT: i32 = 4;
+
+/// Characters on layers above the base layer, like greek on Neo, are less
+/// likely to be typed so count as partly covered
+const UPPER_LAYER_COVERAGE: f64 = 0.75;
+
+/// Fewest keys in a line counted as a walk
+const MIN_WALK: usize = 3;
+
+/// Walks only count when their share of keystrokes is this much more than
+/// the usual share, less is put down to chance
+const WALK_TOLERANCE: f64 = 0.1;
+
+/// Ordinary text giving the usual share of keystrokes in walks on a layout
+static REFERENCE: &str = "when we arrived at the station the train had already \
+                          left so we waited for an hour and talked about our \
+                          plans for the summer holidays with some friends who \
+                          were also going home for the weekend. there is a \
+                          house in the middle of the street where my sister \
+                          used to live before she moved to the city with her \
+                          family to be closer to work and the schools for her \
+                          two children. please find attached the latest \
+                          version of the document we discussed at the meeting \
+                          last week and let me know if you have any questions";
+
+lazy_static! {
+    /// Usual share of keystrokes in walks on each of the built in layouts
+    static ref USUAL_WALKS: Vec<(&'static Keyboard, f64)> =
+        LAYOUTS.iter().map(|&(_, k)| (k, reference_walks(k))).collect();
+}
+
+/// A keystroke from a log
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Keystroke {
+    /// The character typed
+    pub character: char,
+    /// Whether shift was held
+    pub shift: bool,
+}
+
+/// How likely a layout is for a text sample
+#[derive(Debug, PartialEq, Clone)]
+pub struct LayoutScore {
+    /// Name of the layout
+    pub name: &'static str,
+    /// Share of characters, ignoring whitespace, the layout can type.
+    /// Characters on upper layers count for less.
+    pub coverage: f64,
+    /// Share of keystrokes in walks, three or more keys in a line. These only
+    /// add to the score when well above the share in ordinary text.
+    pub walks: f64,
+    /// Share of logged keystrokes the layout types with the shift state
+    /// logged, 1 when shift isn't known
+    pub shift: f64,
+    /// Combined score, higher is more likely
+    pub score: f64,
+}
+
+/// Returns the share of keystrokes in runs of keys in a line in the same
+/// direction, as in a walk.
+fn walk_share(keyboard: &Keyboard, words: &[Vec<Option<Key>>]) -> f64 {
+    let mut walked = 0;
+    let mut total = 0;
+    for keys in words {
+        total += keys.len();
+        let mut run = 1;
+        let mut direction = None;
+        for i in 1..=keys.len() {
+            let step = match (keys[i - 1], keys.get(i).cloned().and_then(|k| k)) {
+                (Some(a), Some(b)) => keyboard.edge_weight(a, b)
+                                              .cloned()
+                                              .filter(|e| !e.is_same_key()),
+                _ => None,
+            };
+            match step {
+                Some(e) if direction.is_none() || direction == Some(e) => run += 1,
+                _ => {
+                    if run >= MIN_WALK {
+                        walked += run;
+                    }
+                    run = if step.is_some() { 2 } else { 1 };
+                },
+            }
+            direction = step;
+        }
+    }
+    if total == 0 { 0.0 } else { (walked as f64 / total as f64).min(1.0) }
+}
+
+/// Finds the keys typing each word of some text
+fn find_words(keyboard: &Keyboard, text: &str) -> Vec<Vec<Option<Key>>> {
+    text.split_whitespace().map(|w| keyboard.find_keys(w)).collect()
+}
+
+/// Returns the share of keystrokes in walks when typing the reference text
+fn reference_walks(keyboard: &Keyboard) -> f64 {
+    walk_share(keyboard, &find_words(keyboard, REFERENCE))
+}
+
+/// Returns the usual share of keystrokes in walks on a layout, only working
+/// it out for layouts which aren't built in
+fn usual_walks(keyboard: &Keyboard) -> f64 {
+    USUAL_WALKS.iter()
+               .find(|&&(k, _)| ptr::eq(k, keyboard))
+               .map(|&(_, share)| share)
+               .unwrap_or_else(|| reference_walks(keyboard))
+}
+
+fn score_words(name: &'static str,
+               keyboard: &Keyboard,
+               words: &[Vec<Option<Key>>],
+               shift: f64) -> LayoutScore {
+    let total = words.iter().map(|w| w.len()).sum::<usize>();
+    let typed = words.iter()
+                     .flat_map(|w| w.iter().filter_map(|k| *k))
+                     .map(|k| if keyboard.find_layer(k) == 0 { 1.0 } else { UPPER_LAYER_COVERAGE })
+                     .sum::<f64>();
+    let coverage = if total == 0 { 0.0 } else { typed / total as f64 };
+    let walks = walk_share(keyboard, words);
+    let usual = usual_walks(keyboard);
+    let walk_score = if walks > usual + WALK_TOLERANCE { walks } else { 0.0 };
+    LayoutScore {
+        name,
+        coverage,
+        walks,
+        shift,
+        score: coverage.powi(COVERAGE_EXPONENT) * shift.powi(COVERAGE_EXPONENT) *
+               (1.0 + WALK_WEIGHT * walk_score),
+    }
+}
+
+/// Scores a layout for a text sample
+pub fn score(name: &'static str, keyboard: &Keyboard, text: &str) -> LayoutScore {
+    score_words(name, keyboard, &find_words(keyboard, text), 1.0)
+}
+
+/// Scores a layout for a keystroke log
+pub fn score_keystrokes(name: &'static str,
+                        keyboard: &Keyboard,
+                        keystrokes: &[Keystroke]) -> LayoutScore {
+    let mut words = vec![Vec::new()];
+    let mut agreed = 0;
+    let mut known = 0;
+    for k in keystrokes {
+        if k.character.is_whitespace() {
+            words.push(Vec::new());
+            continue;
+        }
+        if let Some(level) = keyboard.find_level(k.character) {
+            known += 1;
+            if level.shifted == k.shift {
+                agreed += 1;
+            }
+        }
+        words.last_mut().unwrap().push(keyboard.find_key(k.character));
+    }
+    let shift = if known == 0 { 1.0 } else { agreed as f64 / known as f64 };
+    score_words(name, keyboard, &words, shift)
+}
+
+fn sorted(mut scores: Vec<LayoutScore>) -> Vec<LayoutScore> {
+    scores.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    scores
+}
+
+/// Ranks layouts by how likely they are to have been used to type the text,
+/// most likely first. Layouts scoring the same keep their order so list more
+/// common layouts first.
+pub fn rank(text: &str, layouts: &[(&'static str, &Keyboard)]) -> Vec<LayoutScore> {
+    sorted(layouts.iter().map(|&(name, keyboard)| score(name, keyboard, text)).collect())
+}
+
+/// Ranks layouts by how likely they are to have been used to type the
+/// keystrokes, as `rank`.
+pub fn rank_keystrokes(keystrokes: &[Keystroke],
+                       layouts: &[(&'static str, &Keyboard)]) -> Vec<LayoutScore> {
+    sorted(layouts.iter()
+                  .map(|&(name, keyboard)| score_keystrokes(name, keyboard, keystrokes))
+                  .collect())
+}
+
+/// Returns the name of the most likely of the built in layouts to have been
+/// used to type the text, or None if none of them can type any of it.
+pub fn infer(text: &str) -> Option<&'static str> {
+    rank(text, &LAYOUTS).into_iter()
+                        .find(|s| s.score > 0.0)
+                        .map(|s| s.name)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use infer::*;
+
+    #[test]
+    fn test_rank() {
+        let layouts = [("QWERTY_US", &*QWERTY_US),
+                       ("QWERTY_UK", &*QWERTY_UK),
+                       ("DVORAK", &*DVORAK),
+                       ("RUSSIAN", &*RUSSIAN)];
+        let names = |text| rank(text, &layouts).into_iter().map(|s| s.name).collect::<Vec<_>>();
+
+        assert_eq!(names("qwerty asdf")[0], "QWERTY_US");
+        assert_eq!(names("£20 please")[0], "QWERTY_UK");
+        assert_eq!(names("aoeu htns")[0], "DVORAK");
+        assert_eq!(names("привет")[0], "RUSSIAN");
+
+        let scores = rank("£5", &layouts);
+        let us = scores.iter().find(|s| s.name == "QWERTY_US").unwrap();
+        assert_eq!(us.coverage, 0.5);
+        let uk = scores.iter().find(|s| s.name == "QWERTY_UK").unwrap();
+        assert_eq!(uk.coverage, 1.0);
+        assert_eq!(uk.walks, 0.0);
+    }
+
+    #[test]
+    fn test_prose() {
+        let prose = ["hello world",
+                     "the quick brown fox jumps over the lazy dog",
+                     "please send the report to the team before friday",
+                     "it was the best of times it was the worst of times it was \
+                      the age of wisdom it was the age of foolishness",
+                     "password"];
+        for text in prose.iter() {
+            assert_eq!(rank(text, &LAYOUTS)[0].name, "QWERTY_US");
+        }
+    }
+
+    #[test]
+    fn test_shift() {
+        // The shifted number row differs between US and UK
+        assert_eq!(infer("!@#$%"), Some("QWERTY_US"));
+        assert_eq!(infer("!\"£$%"), Some("QWERTY_UK"));
+
+        // # is shifted on US keyboards but not UK ones
+        let log = [Keystroke { character: '#', shift: false },
+                   Keystroke { character: '1', shift: false }];
+        let scores = rank_keystrokes(&log, &[("QWERTY_US", &*QWERTY_US),
+                                             ("QWERTY_UK", &*QWERTY_UK)]);
+        assert_eq!(scores[0].name, "QWERTY_UK");
+        assert_eq!(scores[1].shift, 0.5);
+        assert_eq!(scores[0].shift, 1.0);
+    }
+
+    #[test]
+    fn test_infer() {
+        assert_eq!(infer("arst neio"), Some("COLEMAK"));
+        assert_eq!(infer("γειά σου"), Some("GREEK"));
+        assert_eq!(infer("かな"), Some("JIS"));
+        assert_eq!(infer("   "), None);
+    }
+}
@@ -0,0 +1,62 @@
+//! Conversion of kana into the keys pressed to type them in kana input mode.
+//!
+//! Kana keyboards only have keys for the unvoiced hiragana. Voiced (が) and
+//! semi-voiced (ぱ) kana are typed by following the unvoiced kana with the 
+//! dakuten (゛) or handakuten (゜) key, and katakana is produced by converting
+//! hiragana in the input method.
+
+/// Hiragana typed by adding a dakuten to the preceding code point
+static VOICED: &str = "がぎぐげござじずぜぞだぢづでどばびぶべぼ";
+/// Hiragana typed by adding a handakuten, these are two code points after the
+/// unvoiced kana
+static SEMI_VOICED: &str = "ぱぴぷぺぽ";
+
+/// Dakuten key
+pub const DAKUTEN: char = '゛';
+/// Handakuten key
+pub const HANDAKUTEN: char = '゜';
+
+/// Converts katakana to the hiragana typed to produce it. Other characters are
+/// returned unchanged.
+pub fn to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => std::char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Returns the characters typed to produce `c` in kana input mode. Characters
+/// which aren't kana are returned unchanged.
+pub fn keystrokes(c: char) -> Vec<char> {
+    let c = to_hiragana(c);
+    if VOICED.contains(c) {
+        vec![std::char::from_u32(c as u32 - 1).unwrap(), DAKUTEN]
+    } else if SEMI_VOICED.contains(c) {
+        vec![std::char::from_u32(c as u32 - 2).unwrap(), HANDAKUTEN]
+    } else {
+        match c {
+            'ゔ' => vec!['う', DAKUTEN],
+            // Combining forms of the sound marks
+            '\u{3099}' => vec![DAKUTEN],
+            '\u{309A}' => vec![HANDAKUTEN],
+            _ => vec![c],
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use kana::*;
+
+    #[test]
+    fn test_keystrokes() {
+        assert_eq!(keystrokes('か'), vec!['か']);
+        assert_eq!(keystrokes('が'), vec!['か', DAKUTEN]);
+        assert_eq!(keystrokes('ガ'), vec!['か', DAKUTEN]);
+        assert_eq!(keystrokes('ぽ'), vec!['ほ', HANDAKUTEN]);
+        assert_eq!(keystrokes('ヴ'), vec!['う', DAKUTEN]);
+        assert_eq!(keystrokes('ー'), vec!['ー']);
+        assert_eq!(keystrokes('a'), vec!['a']);
+    }
+}
@@ -1,8 +1,34 @@
+// The newer std methods these lints suggest would raise the minimum Rust
+// version for the whole crate.
+#![allow(clippy::manual_is_multiple_of, clippy::manual_repeat_n, clippy::unnecessary_map_or)]
+
 #[macro_use]
 extern crate lazy_static;
 extern crate petgraph;
+extern crate unicode_normalization;
+extern crate unicode_segmentation;
+
+pub mod cluster;
+pub mod convert;
+pub mod correction;
+pub mod deadkey;
+pub mod distance;
+pub mod geometry;
+pub mod hangul;
+pub mod infer;
+pub mod kana;
+pub mod mobile;
+pub mod pin;
+pub mod script;
+pub mod squatting;
+pub mod suggest;
+pub mod t9;
+pub mod typo;
+pub mod walk;
 
+use std::fmt;
 pub use petgraph::graphmap::DiGraphMap;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub type Keyboard = DiGraphMap<Key, Edge>;
 
@@ -10,12 +36,21 @@ pub type Keyboard = DiGraphMap<Key, Edge>;
 #[derive(Hash, Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Key {
     /// Value of the key
-    pub value: char, 
+    pub value: Output, 
     /// Value when shift is pressed
-    pub shifted: char,
+    pub shifted: Output,
 }
 
 impl Key {
+    /// Creates a key typing a single character with and without shift, use
+    /// \0 where it types nothing.
+    pub fn new(value: char, shifted: char) -> Key {
+        Key {
+            value: Output::from(value),
+            shifted: Output::from(shifted),
+        }
+    }
+
     pub fn is_shifted(&self, val: char) -> bool {
         self.shifted == val && val != '\0'
     }
@@ -23,6 +58,155 @@ impl Key {
     pub fn is_unshifted(&self, val: char) -> bool {
         self.value == val && val != '\0'
     }
+
+    /// Returns the text typed by the key without shift. This is usually a
+    /// single character however some keys type a cluster of characters (see
+    /// `cluster`).
+    pub fn value_output(&self) -> String {
+        self.value.to_string()
+    }
+
+    /// Returns the text typed by the key with shift.
+    pub fn shifted_output(&self) -> String {
+        self.shifted.to_string()
+    }
+}
+
+/// Text typed by a key on one level. This is usually a single character but
+/// some keys type a cluster of several code points (see `cluster`). The text
+/// is held in place so keys can be copied, an empty output is written \0.
+#[derive(Hash, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
+pub struct Output {
+    /// UTF-8 bytes of the text padded with zeros
+    bytes: [u8; OUTPUT_BYTES],
+}
+
+/// Longest output in bytes
+const OUTPUT_BYTES: usize = 12;
+
+impl Output {
+    /// Creates an output typing some text. Returns None if the text is too
+    /// long for a key or contains \0.
+    pub fn new(text: &str) -> Option<Output> {
+        if text.len() > OUTPUT_BYTES || text.contains('\0') {
+            return None;
+        }
+        let mut bytes = [0; OUTPUT_BYTES];
+        bytes[..text.len()].copy_from_slice(text.as_bytes());
+        Some(Output { bytes })
+    }
+
+    /// Returns the text typed
+    pub fn as_str(&self) -> &str {
+        let len = self.bytes.iter().position(|b| *b == 0).unwrap_or(OUTPUT_BYTES);
+        std::str::from_utf8(&self.bytes[..len]).expect("Output isn't UTF-8")
+    }
+
+    /// Returns true if nothing is typed
+    pub fn is_empty(&self) -> bool {
+        self.bytes[0] == 0
+    }
+
+    /// Returns true if the output is a cluster of several characters
+    pub fn is_cluster(&self) -> bool {
+        let width = match self.bytes[0] {
+            0x00..=0x7F => 1,
+            0x80..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            _ => 4,
+        };
+        width < OUTPUT_BYTES && self.bytes[width] != 0
+    }
+
+    /// Returns the character typed, or None if the output is empty or a
+    /// cluster of several characters.
+    pub fn as_char(&self) -> Option<char> {
+        let mut chars = self.as_str().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl From<char> for Output {
+    fn from(c: char) -> Output {
+        let mut buffer = [0; 4];
+        Output::new(c.encode_utf8(&mut buffer)).unwrap_or_default()
+    }
+}
+
+impl Default for Output {
+    fn default() -> Output {
+        Output { bytes: [0; OUTPUT_BYTES] }
+    }
+}
+
+/// An output equals a character if it types only that character, an empty
+/// output equals \0.
+impl PartialEq<char> for Output {
+    fn eq(&self, other: &char) -> bool {
+        let mut buffer = [0; 4];
+        let encoded = other.encode_utf8(&mut buffer).as_bytes();
+        self.bytes[..encoded.len()] == *encoded &&
+            self.bytes.get(encoded.len()).map_or(true, |b| *b == 0)
+    }
+}
+
+impl PartialEq<Output> for char {
+    fn eq(&self, other: &Output) -> bool {
+        other == self
+    }
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Output {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.as_char() {
+            Some(c) => write!(f, "{:?}", c),
+            None if self.is_empty() => write!(f, "{:?}", '\0'),
+            None => write!(f, "{:?}", self.as_str()),
+        }
+    }
+}
+
+/// Rules for finding the uppercase form of a letter, which the shifted value
+/// of a letter key is. These differ by locale.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Casing {
+    /// Default unicode case mapping
+    Default,
+    /// Turkish and Azerbaijani case mapping, where i uppercases to İ and the
+    /// dotless ı uppercases to I
+    Turkic,
+}
+
+impl Casing {
+    /// Returns the uppercase form of a letter or \0 if there isn't exactly one
+    /// character it maps to.
+    pub fn uppercase(&self, c: char) -> char {
+        match (*self, c) {
+            (Casing::Turkic, 'i') => 'İ',
+            (Casing::Turkic, 'ı') => 'I',
+            _ => uppercase(c),
+        }
+    }
+}
+
+/// The modifiers needed to type a character. Layers are numbered upwards from
+/// the base keys at 0, on most keyboards layer 1 is reached with AltGr. Each
+/// layout documents the modifiers for its layers.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Level {
+    /// Layer of the key typing the character
+    pub layer: usize,
+    /// Whether shift is also held
+    pub shifted: bool,
 }
 
 /// Trait to find a key given a single character from it. This function is 
@@ -32,6 +216,52 @@ pub trait KeySearch {
     /// Finds the key given a char from it. 
     /// Returns Some(Key) if a key exists else returns None.
     fn find_key(&self, v: char) -> Option<Key>;
+
+    /// Finds the key for each keystroke typing some text in the order they 
+    /// were typed. The text is normalised first (see `script::normalise`) so
+    /// text from right-to-left or contextually shaped scripts can be searched.
+    /// Accented letters without a key of their own are found as a dead key and
+    /// the letter (see `deadkey`). By default each character is found with 
+    /// `find_key`.
+    fn find_keys(&self, text: &str) -> Vec<Option<Key>> {
+        text.chars().map(|c| self.find_key(c)).collect()
+    }
+
+    /// Splits text into grapheme clusters and finds the keys typed to produce
+    /// each one. Keys typing several code points at once are preferred to 
+    /// typing them individually. A cluster is None if any part of it can't be
+    /// typed on the keyboard. By default each character of a cluster is found
+    /// with `find_key`.
+    fn find_clusters(&self, text: &str) -> Vec<Option<Vec<Key>>> {
+        text.graphemes(true)
+            .map(|g| g.chars().map(|c| self.find_key(c)).collect())
+            .collect()
+    }
+
+    /// Finds the keys on other layers of the same physical key, i.e. the kana
+    /// sharing a key with a latin letter. Returns an empty vector for keyboards
+    /// with a single layer, which is the default. On touchscreen keyboards 
+    /// this is both the long pressed characters and the key in the same place
+    /// on the other pages, use `mobile::long_press_alternates` for only the 
+    /// long pressed ones.
+    fn find_alternates(&self, _key: Key) -> Vec<Key> {
+        Vec::new()
+    }
+
+    /// Finds the layer a key is on, 0 for keys on the base layer. By default
+    /// every key is on the base layer.
+    fn find_layer(&self, _key: Key) -> usize {
+        0
+    }
+
+    /// Finds the layer and shift state needed to type a character.
+    /// Returns None if the character isn't on the keyboard.
+    fn find_level(&self, v: char) -> Option<Level> {
+        self.find_key(v).map(|k| Level {
+            layer: self.find_layer(k),
+            shifted: k.value != v,
+        })
+    }
 }
 
 /// Implementation of KeySearch for the graph used to hold keys
@@ -40,14 +270,166 @@ impl KeySearch for DiGraphMap<Key, Edge> {
         if v == '\0' {
             None
         } else {
+            let v = Output::from(v);
             self.nodes().filter(|x| x.value == v || x.shifted == v).nth(0)
         }
     }
+
+    fn find_keys(&self, text: &str) -> Vec<Option<Key>> {
+        text.graphemes(true)
+            .flat_map(|g| cluster_keystrokes(self, g))
+            .collect()
+    }
+
+    fn find_clusters(&self, text: &str) -> Vec<Option<Vec<Key>>> {
+        text.graphemes(true)
+            .map(|g| cluster_keystrokes(self, g))
+            .filter(|keys| !keys.is_empty())
+            .map(|keys| keys.into_iter().collect())
+            .collect()
+    }
+
+    fn find_alternates(&self, key: Key) -> Vec<Key> {
+        let mut result = vec![key];
+        let mut i = 0;
+        while i < result.len() {
+            let current = result[i];
+            let layers = self.neighbors_directed(current, petgraph::Direction::Outgoing)
+                             .chain(self.neighbors_directed(current, petgraph::Direction::Incoming))
+                             .filter(|n| self.edge_weight(current, *n)
+                                             .or_else(|| self.edge_weight(*n, current))
+                                             .is_some_and(|e| e.is_same_key()))
+                             .collect::<Vec<_>>();
+            for n in layers {
+                if !result.contains(&n) {
+                    result.push(n);
+                }
+            }
+            i += 1;
+        }
+        result.remove(0);
+        result
+    }
+
+    fn find_layer(&self, key: Key) -> usize {
+        let mut layer = 0;
+        let mut current = mobile::pressed_key(self, key).unwrap_or(key);
+        while let Some(lower) = lower_layer(self, current) {
+            layer += 1;
+            current = lower;
+        }
+        layer
+    }
+}
+
+/// Trait to move around a keyboard by the directions of its edges
+pub trait KeyWalk {
+    /// Finds the neighbour of a key in a direction. Returns None if there's 
+    /// no key there. Other layers of the same key aren't neighbours so a
+    /// direction where both are `Same` gives None.
+    fn step(&self, key: Key, direction: Edge) -> Option<Key>;
+
+    /// Types the keys reached by stepping in each direction in turn from the
+    /// start key. The shift mask gives whether each key, starting with the 
+    /// first, is pressed with shift, keys past the end of it aren't. Returns 
+    /// None if the walk runs off the keyboard.
+    fn walk(&self, start: Key, directions: &[Edge], shift_mask: &[bool]) -> Option<String>;
+
+    /// Encodes text as a walk, see `walk::encode`.
+    fn encode_walk(&self, text: &str) -> Option<walk::Walk>;
+}
+
+/// Implementation of KeyWalk for the graph used to hold keys
+impl KeyWalk for DiGraphMap<Key, Edge> {
+    fn step(&self, key: Key, direction: Edge) -> Option<Key> {
+        if direction.is_same_key() {
+            return None;
+        }
+        self.neighbors_directed(key, petgraph::Direction::Outgoing)
+            .find(|n| self.edge_weight(key, *n) == Some(&direction))
+    }
+
+    fn walk(&self, start: Key, directions: &[Edge], shift_mask: &[bool]) -> Option<String> {
+        let mut keys = vec![start];
+        for direction in directions {
+            let next = self.step(keys[keys.len() - 1], *direction)?;
+            keys.push(next);
+        }
+        let mut result = String::new();
+        for (i, k) in keys.iter().enumerate() {
+            let output = if shift_mask.get(i).cloned().unwrap_or(false) {
+                k.shifted_output()
+            } else {
+                k.value_output()
+            };
+            if output.is_empty() {
+                return None;
+            }
+            result.push_str(&output);
+        }
+        Some(result)
+    }
+
+    fn encode_walk(&self, text: &str) -> Option<walk::Walk> {
+        walk::encode(self, text)
+    }
+}
+
+/// Finds the key on the layer below a key, joined to it by a same key edge.
+/// Long pressed keys are joined to the key pressed by same key edges both
+/// ways as they're on the same layer, so these aren't followed.
+fn lower_layer(graph: &Keyboard, key: Key) -> Option<Key> {
+    let same_key = |a, b| graph.edge_weight(a, b).is_some_and(|e| e.is_same_key());
+    graph.neighbors_directed(key, petgraph::Direction::Incoming)
+         .find(|n| same_key(*n, key) && !same_key(key, *n))
+}
+
+/// Finds the keys typed to produce a grapheme cluster. Where the keyboard has a
+/// key typing several code points of the cluster at once it's used instead of
+/// typing each code point.
+fn cluster_keystrokes(graph: &Keyboard, grapheme: &str) -> Vec<Option<Key>> {
+    let chars = script::normalise(grapheme).chars().collect::<Vec<_>>();
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let cluster = cluster::prefixes(graph, &chars[i..]).into_iter().next();
+        match cluster {
+            Some((key, len)) => {
+                result.push(Some(key));
+                i += len;
+            },
+            None => {
+                match graph.find_key(chars[i]) {
+                    Some(key) => result.push(Some(key)),
+                    None => match dead_key_strokes(graph, chars[i]) {
+                        Some((dead, letter)) => {
+                            result.push(Some(dead));
+                            result.push(Some(letter));
+                        },
+                        None => result.push(None),
+                    },
+                }
+                i += 1;
+            },
+        }
+    }
+    result
+}
+
+/// Finds the dead key and letter keys typing an accented letter.
+fn dead_key_strokes(graph: &Keyboard, c: char) -> Option<(Key, Key)> {
+    deadkey::keystrokes(c)
+        .into_iter()
+        .filter_map(|(dead, letter)| match (graph.find_key(dead), graph.find_key(letter)) {
+            (Some(d), Some(l)) => Some((d, l)),
+            _ => None,
+        })
+        .next()
 }
 
 /// Enum representing a direction relative to a key on either the horizontal or
 /// vertical axis
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Direction {
     /// Previous refers to above or left to the key 
     Previous = -1, 
@@ -59,7 +441,13 @@ pub enum Direction {
 
 /// Struct to represent the relative positioning of one key to a neighbouring 
 /// key
-#[derive(Debug, PartialEq, Clone, Copy)]
+///
+/// When both directions are `Same` the edge joins two outputs of the same 
+/// physical key on different layers (i.e. latin and kana on a JIS keyboard).
+/// These edges point from the lower layer to the higher one. Keys typed by
+/// long pressing a key on a touchscreen are on the same layer as it, so they
+/// are joined to it by these edges both ways.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Edge {
     /// Relative horizontal position
     pub horizontal: Direction, 
@@ -67,6 +455,13 @@ pub struct Edge {
     pub vertical: Direction, 
 }
 
+impl Edge {
+    /// Returns true if the edge joins two layers of the same physical key
+    pub fn is_same_key(&self) -> bool {
+        self.horizontal == Direction::Same && self.vertical == Direction::Same
+    }
+}
+
 /// Keyboard style. The main part of a keyboard normally applies a slant to the
 /// rows meaning that a key only has 6 neighbours, however numpads are aligned
 /// meaning that they have more neighbours. This enum allows for distinguishing
@@ -109,19 +504,123 @@ fn get_aligned_positions() -> Vec<Edge> {
     ]
 }
 
-/// Keyboards exported to the user.
+// Keyboards exported to the user.
 lazy_static! {
     pub static ref QWERTY_US: Keyboard = generate_qwerty_us();
     pub static ref QWERTY_UK: Keyboard = generate_qwerty_uk();
     pub static ref DVORAK: Keyboard = generate_dvorak(); 
+    pub static ref PROGRAMMER_DVORAK: Keyboard = generate_programmer_dvorak();
+    pub static ref DVORAK_UK: Keyboard = generate_dvorak_uk();
+    pub static ref DVORAK_LEFT: Keyboard = generate_dvorak_left();
+    pub static ref DVORAK_RIGHT: Keyboard = generate_dvorak_right();
+    pub static ref COLEMAK: Keyboard = generate_colemak();
+    pub static ref COLEMAK_DH: Keyboard = generate_colemak_dh();
+    pub static ref COLEMAK_DH_ISO: Keyboard = generate_colemak_dh_iso();
+    pub static ref WORKMAN: Keyboard = generate_workman();
+    pub static ref RUSSIAN: Keyboard = generate_russian();
+    pub static ref UKRAINIAN: Keyboard = generate_ukrainian();
+    pub static ref BULGARIAN_PHONETIC: Keyboard = generate_bulgarian_phonetic();
+    pub static ref GREEK: Keyboard = generate_greek();
+    pub static ref HEBREW: Keyboard = generate_hebrew();
+    pub static ref ARABIC: Keyboard = generate_arabic();
+    pub static ref JIS: Keyboard = generate_jis();
+    pub static ref DUBEOLSIK: Keyboard = generate_dubeolsik();
+    pub static ref INSCRIPT: Keyboard = generate_inscript();
+    pub static ref KEDMANEE: Keyboard = generate_kedmanee();
+    pub static ref ZHUYIN: Keyboard = generate_zhuyin();
+    pub static ref SWEDISH: Keyboard = generate_swedish();
+    pub static ref NORWEGIAN: Keyboard = generate_norwegian();
+    pub static ref DANISH: Keyboard = generate_danish();
+    pub static ref FINNISH: Keyboard = generate_finnish();
+    pub static ref ICELANDIC: Keyboard = generate_icelandic();
+    pub static ref SPANISH: Keyboard = generate_spanish();
+    pub static ref LATIN_AMERICAN: Keyboard = generate_latin_american();
+    pub static ref ITALIAN: Keyboard = generate_italian();
+    pub static ref PORTUGUESE: Keyboard = generate_portuguese();
+    pub static ref ABNT2: Keyboard = generate_abnt2();
+    pub static ref TURKISH_Q: Keyboard = generate_turkish_q();
+    pub static ref TURKISH_F: Keyboard = generate_turkish_f();
+    pub static ref BEPO: Keyboard = generate_bepo();
+    pub static ref NEO2: Keyboard = generate_neo2();
+    pub static ref IOS: Keyboard = generate_ios();
+    pub static ref ANDROID: Keyboard = generate_android();
     pub static ref STANDARD_NUMPAD: Keyboard = generate_standard_numpad();
     pub static ref MAC_NUMPAD: Keyboard = generate_mac_numpad();
+    pub static ref PHONE_KEYPAD: Keyboard = generate_phone_keypad();
+}
+
+lazy_static! {
+    /// The full size keyboard layouts by name. Numpads and mobile keyboards 
+    /// are left out as their keys aren't where a full size keyboard's are.
+    pub static ref LAYOUTS: Vec<(&'static str, &'static Keyboard)> = vec![
+        ("QWERTY_US", &*QWERTY_US),
+        ("QWERTY_UK", &*QWERTY_UK),
+        ("DVORAK", &*DVORAK),
+        ("PROGRAMMER_DVORAK", &*PROGRAMMER_DVORAK),
+        ("DVORAK_UK", &*DVORAK_UK),
+        ("DVORAK_LEFT", &*DVORAK_LEFT),
+        ("DVORAK_RIGHT", &*DVORAK_RIGHT),
+        ("COLEMAK", &*COLEMAK),
+        ("COLEMAK_DH", &*COLEMAK_DH),
+        ("COLEMAK_DH_ISO", &*COLEMAK_DH_ISO),
+        ("WORKMAN", &*WORKMAN),
+        ("RUSSIAN", &*RUSSIAN),
+        ("UKRAINIAN", &*UKRAINIAN),
+        ("BULGARIAN_PHONETIC", &*BULGARIAN_PHONETIC),
+        ("GREEK", &*GREEK),
+        ("HEBREW", &*HEBREW),
+        ("ARABIC", &*ARABIC),
+        ("JIS", &*JIS),
+        ("DUBEOLSIK", &*DUBEOLSIK),
+        ("INSCRIPT", &*INSCRIPT),
+        ("KEDMANEE", &*KEDMANEE),
+        ("ZHUYIN", &*ZHUYIN),
+        ("SWEDISH", &*SWEDISH),
+        ("NORWEGIAN", &*NORWEGIAN),
+        ("DANISH", &*DANISH),
+        ("FINNISH", &*FINNISH),
+        ("ICELANDIC", &*ICELANDIC),
+        ("SPANISH", &*SPANISH),
+        ("LATIN_AMERICAN", &*LATIN_AMERICAN),
+        ("ITALIAN", &*ITALIAN),
+        ("PORTUGUESE", &*PORTUGUESE),
+        ("ABNT2", &*ABNT2),
+        ("TURKISH_Q", &*TURKISH_Q),
+        ("TURKISH_F", &*TURKISH_F),
+        ("BEPO", &*BEPO),
+        ("NEO2", &*NEO2),
+    ];
 }
 
 
 /// Convenience strings to iterate over.
-static ALPHABET: &'static str = "abcdefghijklmnopqrstuvwxyz";
-static NUMBERS: &'static str = "0123456789";
+static ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
+static NUMBERS: &str = "0123456789";
+static RUSSIAN_ALPHABET: &str = "абвгдеёжзийклмнопрстуфхцчшщъыьэюя";
+static UKRAINIAN_ALPHABET: &str = "абвгґдеєжзиіїйклмнопрстуфхцчшщьюя";
+static BULGARIAN_ALPHABET: &str = "абвгдежзийклмнопрстуфхцчшщъьюя";
+static GREEK_ALPHABET: &str = "αβγδεζηθικλμνξοπρστυφχψω";
+static TURKISH_ALPHABET: &str = "abcçdefgğhıijklmnoöpqrsştuüvwxyz";
+/// Characters typed by long pressing keys on touchscreen keyboards
+static MOBILE_LONG_PRESS: &[(char, &str)] = &[
+    ('a', "àáâäæãåā"),
+    ('c', "çćč"),
+    ('e', "èéêëēėę"),
+    ('i', "îïíīįì"),
+    ('l', "ł"),
+    ('n', "ñń"),
+    ('o', "ôöòóœøōõ"),
+    ('s', "ßśš"),
+    ('u', "ûüùúū"),
+    ('y', "ÿ"),
+    ('z', "žźż"),
+    ('-', "–—"),
+    ('.', "…"),
+    ('?', "¿"),
+    ('!', "¡"),
+    ('\'', "‘’"),
+    ('"', "„“”"),
+];
 
 
 /// Function to add all alphabet characters to keyboard. (a-z & A-Z).
@@ -131,14 +630,58 @@ static NUMBERS: &'static str = "0123456789";
 /// This function takes a graph representing the keyboard as an argument so it
 /// can insert the nodes
 fn add_alphabetics(graph: &mut Keyboard) {
-    for c in ALPHABET.chars() {
+    add_letters(ALPHABET, graph);
+}
+
+/// Adds a key for each lowercase letter in `letters` with the uppercase letter
+/// as the shifted value. This works for any script with case (i.e. latin or
+/// cyrillic), letters without a single character uppercase form have no 
+/// shifted value.
+fn add_letters(letters: &str, graph: &mut Keyboard) {
+    add_cased_letters(letters, Casing::Default, graph);
+}
+
+/// Adds a key for each lowercase letter in `letters` with the uppercase letter
+/// given by the casing rules as the shifted value.
+fn add_cased_letters(letters: &str, casing: Casing, graph: &mut Keyboard) {
+    for c in letters.chars() {
+        graph.add_node(Key::new(c, casing.uppercase(c)));
+    }
+}
+
+/// Returns the uppercase form of a letter or \0 if there isn't exactly one
+/// character it maps to. Final forms such as ς share the uppercase of another
+/// letter (σ) so they have no uppercase form of their own.
+fn uppercase(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) if u != c && u.to_lowercase().eq(c.to_lowercase()) => u,
+        _ => '\0',
+    }
+}
+
+/// Adds a key for each pair of outputs, outputs can type several code points
+/// (see `cluster`).
+fn add_output_pairs(keys: &[(&str, &str)], graph: &mut Keyboard) {
+    for &(v, s) in keys.iter() {
         graph.add_node(Key {
-            value: c,
-            shifted: c.to_uppercase().nth(0).unwrap(),
+            value: Output::new(v).expect("Output too long"),
+            shifted: Output::new(s).expect("Output too long"),
         });
     }
 }
 
+/// Adds a key for each character in `values` with the character at the same
+/// position in `shifted` as its shifted value. This is for scripts without 
+/// case where shift gives punctuation or diacritics instead, use \0 if shift
+/// gives nothing.
+fn add_shifted_pairs(values: &str, shifted: &str, graph: &mut Keyboard) {
+    assert_eq!(values.chars().count(), shifted.chars().count());
+    for (v, s) in values.chars().zip(shifted.chars()) {
+        graph.add_node(Key::new(v, s));
+    }
+}
+
 /// Numpads typically have no shift modifiers so use this function to populate
 /// the numeric keys.
 /// 
@@ -147,83 +690,122 @@ fn add_alphabetics(graph: &mut Keyboard) {
 fn add_unshifted_number_keys(graph: &mut Keyboard) {
 
     for c in NUMBERS.chars() {
-        graph.add_node(Key {
-            value: c,
-            shifted: '\0',
-        });
+        graph.add_node(Key::new(c, '\0'));
     }
 }
 
 
+/// Splits the string representation of a keyboard into rows of characters.
+fn keyboard_rows(keyboard: &str) -> Vec<Vec<char>> {
+    keyboard.lines()
+            .map(|x| x.chars().filter(|y| y != &' ').collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>()
+}
+
 /// Given string representation of the keyboard and it's rows and a graph of
 /// nodes this function connects the edges between the nodes. 
 /// 
 /// * keyboard - string representation of the keyboard. Use line breaks to 
-///     separate rows, spaces to delimit chars and \0 on a row to represent
-///     a void area on the keyboard (lines up keys when keys are slanted)
+///   separate rows, spaces to delimit chars and \0 on a row to represent
+///   a void area on the keyboard (lines up keys when keys are slanted)
 /// * graph - graph storing the keyboard adjacency graph
 /// * style - enum representing alignment of keys
 /// * add_missing_keys - whether missing keys should be added to the graph or 
-///     ignored
+///   ignored
 fn connect_keyboard_nodes(keyboard: &str,
                           graph: &mut Keyboard,
                           style: KeyboardStyle,
                           add_missing_keys: bool) {
 
+    let keys = keyboard_rows(keyboard).iter()
+        .map(|row| row.iter().map(|c| {
+            let k = graph.find_key(*c);
+            if k.is_none() && add_missing_keys && *c != '\0' {
+                Some(Key::new(*c, '\0'))
+            } else {
+                k
+            }
+        }).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    connect_key_grid(&keys, graph, style);
+}
+
+/// Connects the edges between keys laid out in rows, a key of None represents
+/// a void area on the keyboard.
+fn connect_key_grid(keys: &[Vec<Option<Key>>],
+                    graph: &mut Keyboard,
+                    style: KeyboardStyle) {
+
     let relative_positions = if style == KeyboardStyle::Slanted {
         get_slanted_positions()
     } else {
         get_aligned_positions()
     };
-    let rows = keyboard.lines()
-                       .map(|x| x.chars().filter(|y| y != &' ').collect::<Vec<char>>())
-                       .collect::<Vec<Vec<char>>>();
 
-    let rowcount = rows.iter().count() as i32;
-    for (i, row) in rows.iter().enumerate() {
+    let rowcount = keys.len() as i32;
+    for (i, row) in keys.iter().enumerate() {
         for (j, key) in row.iter().enumerate() {
             // Get the adjacent keys now
-            let k = graph.find_key(*key);
-            if k.is_none() && !add_missing_keys {
-                continue;
-            }
-            let k = if k.is_some() {
-                k.unwrap()
-            } else {
-                Key {
-                    value: *key,
-                    shifted: '\0',
-                }
+            let k = match *key {
+                Some(k) => k,
+                None => continue,
             };
 
             for dir in relative_positions.iter() {
                 let y: i32 = i as i32 + dir.vertical as i32;
                 let x: i32 = j as i32 + dir.horizontal as i32;
                 if y > -1 && y < rowcount && x > -1 {
-                    let temp_row = if dir.vertical == Direction::Same {
-                        row
+                    if let Some(&Some(n)) = keys[y as usize].get(x as usize) {
+                        graph.add_edge(k, n, *dir);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Connects keys laid out in rows where each row starts at an offset measured
+/// in key widths from the left edge, as on touchscreen keyboards. Keys in
+/// neighbouring rows are adjacent if they are at most a key width apart, keys
+/// less than a quarter of a key apart are vertically aligned.
+fn connect_offset_rows(keys: &[Vec<Option<Key>>],
+                       offsets: &[f32],
+                       graph: &mut Keyboard) {
+    assert_eq!(keys.len(), offsets.len());
+
+    for (i, row) in keys.iter().enumerate() {
+        for (j, key) in row.iter().enumerate() {
+            let k = match *key {
+                Some(k) => k,
+                None => continue,
+            };
+            let x = offsets[i] + j as f32;
+
+            for (dy, vertical) in [(-1, Direction::Previous), (0, Direction::Same), (1, Direction::Next)].iter() {
+                let y = i as i32 + dy;
+                if y < 0 || y >= keys.len() as i32 {
+                    continue;
+                }
+                let y = y as usize;
+                for (m, other) in keys[y].iter().enumerate() {
+                    let n = match *other {
+                        Some(n) if n != k => n,
+                        _ => continue,
+                    };
+                    let dx = offsets[y] + m as f32 - x;
+                    let horizontal = if dx < -0.25 {
+                        Direction::Previous
+                    } else if dx > 0.25 {
+                        Direction::Next
                     } else {
-                        rows.get(y as usize).unwrap()
+                        Direction::Same
                     };
-
-                    if let Some(temp_char) = temp_row.get(x as usize) {
-
-                        let n = graph.find_key(*temp_char);
-                        
-                        if n.is_none() && !add_missing_keys {
-                            continue;
-                        }
-
-                        let n = if n.is_some() {
-                            n.unwrap()
-                        } else {
-                            Key {
-                                value: *temp_char,
-                                shifted: '\0',
-                            }
-                        };
-            
-                        graph.add_edge(k, n, *dir);
+                    if dx.abs() <= 1.0 && !(*dy == 0 && horizontal == Direction::Same) {
+                        graph.add_edge(k, n, Edge {
+                            horizontal,
+                            vertical: *vertical,
+                        });
                     }
                 }
             }
@@ -231,15 +813,212 @@ fn connect_keyboard_nodes(keyboard: &str,
     }
 }
 
+/// Adds a page of keys above the keys of another page laid out by row offsets
+/// (see `connect_offset_rows`). Each key on the page is linked to the nearest
+/// key in the same row of the page below by a same key edge, so the layer of
+/// a key is the page it's on.
+///
+/// Returns the keys of the page so further pages can be added above it.
+fn add_offset_page(lower: &[Vec<Option<Key>>],
+                   lower_offsets: &[f32],
+                   values: &str,
+                   shifted: &str,
+                   offsets: &[f32],
+                   graph: &mut Keyboard) -> Vec<Vec<Option<Key>>> {
+
+    let page = add_key_grid(values, shifted, graph);
+    connect_offset_rows(&page, offsets, graph);
+
+    let same_key = Edge {
+        horizontal: Direction::Same,
+        vertical: Direction::Same,
+    };
+    for (i, row) in page.iter().enumerate() {
+        for (j, key) in row.iter().enumerate() {
+            let x = offsets[i] + j as f32;
+            let nearest = lower.get(i).and_then(|lower_row| {
+                lower_row.iter()
+                         .enumerate()
+                         .filter_map(|(n, k)| k.map(|k| (k, (lower_offsets[i] + n as f32 - x).abs())))
+                         .fold(None, |best: Option<(Key, f32)>, (k, d)| match best {
+                             Some((_, b)) if b <= d => best,
+                             _ => Some((k, d)),
+                         })
+            });
+            if let (&Some(k), Some((n, _))) = (key, nearest) {
+                graph.add_edge(n, k, same_key);
+            }
+        }
+    }
+    page
+}
+
+/// Adds keys typed by long pressing another key. These are linked to the key
+/// pressed by same key edges both ways, which keeps them on its layer, and
+/// have no neighbours of their own. Keys missing from the keyboard are
+/// skipped.
+fn add_long_press(alternates: &[(char, &str)], graph: &mut Keyboard) {
+    let same_key = Edge {
+        horizontal: Direction::Same,
+        vertical: Direction::Same,
+    };
+    for &(base, values) in alternates.iter() {
+        let base = match graph.find_key(base) {
+            Some(k) => k,
+            None => continue,
+        };
+        for v in values.chars() {
+            let k = Key::new(v, uppercase(v));
+            graph.add_node(k);
+            graph.add_edge(base, k, same_key);
+            graph.add_edge(k, base, same_key);
+        }
+    }
+}
+
+/// Adds a layer of keys on top of the keys already in the graph. The layer is
+/// given as two strings with the same shape as the base keyboard string, one
+/// for the unshifted values and one for the shifted. Keys in the layer are 
+/// connected to each other by their positions and to the base key in the same
+/// position by an edge where both directions are `Same`. Use \0 where a key
+/// has nothing on the layer.
+///
+/// Returns the keys of the layer so further layers can be added above it.
+fn add_layer(base: &str,
+             values: &str,
+             shifted: &str,
+             graph: &mut Keyboard,
+             style: KeyboardStyle) -> Vec<Vec<Option<Key>>> {
+
+    let base_keys = keyboard_rows(base).iter()
+        .map(|row| row.iter().map(|c| graph.find_key(*c)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    add_layer_above(&base_keys, values, shifted, graph, style)
+}
+
+/// Adds a layer of keys above the keys of another layer (see `add_layer`).
+/// The number of same key edges followed down to the base keys gives the 
+/// layer a key is on, so a layer should only have keys where the layer below
+/// it does.
+fn add_layer_above(lower: &[Vec<Option<Key>>],
+                   values: &str,
+                   shifted: &str,
+                   graph: &mut Keyboard,
+                   style: KeyboardStyle) -> Vec<Vec<Option<Key>>> {
+
+    let layer_keys = add_key_grid(values, shifted, graph);
+    connect_key_grid(&layer_keys, graph, style);
+
+    let same_key = Edge {
+        horizontal: Direction::Same,
+        vertical: Direction::Same,
+    };
+    for (lower_row, layer_row) in lower.iter().zip(layer_keys.iter()) {
+        for (b, l) in lower_row.iter().zip(layer_row.iter()) {
+            if let (&Some(b), &Some(l)) = (b, l) {
+                graph.add_edge(b, l, same_key);
+            }
+        }
+    }
+    layer_keys
+}
+
+/// Adds a key for each position of two keyboard strings of the same shape,
+/// giving the unshifted and shifted values, and returns the keys laid out in
+/// rows. Positions which are \0 in both are void areas.
+fn add_key_grid(values: &str,
+                shifted: &str,
+                graph: &mut Keyboard) -> Vec<Vec<Option<Key>>> {
+
+    let shifted = keyboard_rows(shifted);
+    let keys = keyboard_rows(values).iter()
+        .zip(shifted.iter())
+        .map(|(row, shifted_row)| {
+            assert_eq!(row.len(), shifted_row.len());
+            row.iter().zip(shifted_row.iter()).map(|(v, s)| {
+                if *v == '\0' && *s == '\0' {
+                    None
+                } else {
+                    Some(Key::new(*v, *s))
+                }
+            }).collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for row in keys.iter() {
+        for k in row.iter().filter_map(|k| *k) {
+            graph.add_node(k);
+        }
+    }
+    keys
+}
+
 /// Any keys the user wants to specify that aren't populated by another function
 /// should be added here.
 fn add_remaining_keys(keys: Vec<Key>, graph: &mut Keyboard) {
 
     for k in keys.iter() {
-        graph.add_node(k.clone());
+        graph.add_node(*k);
     }
 }
 
+/// Returns the number and symbol keys found on a US keyboard. Layouts which
+/// only move these keys around (such as dvorak or colemak) share them.
+fn us_symbol_keys() -> Vec<Key> {
+    vec![ 
+        Key::new('`', '~'),
+        Key::new('1', '!'),
+        Key::new('2', '@'),
+        Key::new('3', '#'),
+        Key::new('4', '$'),
+        Key::new('5', '%'),
+        Key::new('6', '^'),
+        Key::new('7', '&'),
+        Key::new('8', '*'),
+        Key::new('9', '('),
+        Key::new('0', ')'),
+        Key::new('-', '_'),
+        Key::new('=', '+'),
+        Key::new('[', '{'),
+        Key::new(']', '}'),
+        Key::new('\\', '|'),
+        Key::new(';', ':'),
+        Key::new('\'', '\"'),
+        Key::new(',', '<'),
+        Key::new('.', '>'),
+        Key::new('/', '?')
+    ]
+}
+
+/// Returns the number and symbol keys found on a UK keyboard.
+fn uk_symbol_keys() -> Vec<Key> {
+    vec![ 
+        Key::new('`', '¬'),
+        Key::new('1', '!'),
+        Key::new('2', '\"'),
+        Key::new('3', '£'),
+        Key::new('4', '$'),
+        Key::new('5', '%'),
+        Key::new('6', '^'),
+        Key::new('7', '&'),
+        Key::new('8', '*'),
+        Key::new('9', '('),
+        Key::new('0', ')'),
+        Key::new('-', '_'),
+        Key::new('=', '+'),
+        Key::new('[', '{'),
+        Key::new(']', '}'),
+        Key::new('\\', '|'),
+        Key::new(';', ':'),
+        Key::new('\'', '@'),
+        Key::new(',', '<'),
+        Key::new('.', '>'),
+        Key::new('/', '?'),
+        Key::new('#', '~')
+    ]
+}
+
 /// Generates the graph for the qwerty US keyboard layout
 pub fn generate_qwerty_us() -> Keyboard {
     let mut result = DiGraphMap::<Key, Edge>::new();
@@ -251,31 +1030,7 @@ pub fn generate_qwerty_us() -> Keyboard {
                      \0 z x c v b n m , . /";
 
     add_alphabetics(&mut result);
-
-    let remaining_keys = vec![ 
-        Key{ value: '`', shifted: '~'},
-        Key{ value: '1', shifted: '!'},
-        Key{ value: '2', shifted: '@'},
-        Key{ value: '3', shifted: '#'},
-        Key{ value: '4', shifted: '$'},
-        Key{ value: '5', shifted: '%'},
-        Key{ value: '6', shifted: '^'},
-        Key{ value: '7', shifted: '&'},
-        Key{ value: '8', shifted: '*'},
-        Key{ value: '9', shifted: '('},
-        Key{ value: '0', shifted: ')'},
-        Key{ value: '-', shifted: '_'},
-        Key{ value: '=', shifted: '+'},
-        Key{ value: '[', shifted: '{'},
-        Key{ value: ']', shifted: '}'},
-        Key{ value: '\\', shifted: '|'},
-        Key{ value: ';', shifted: ':'},
-        Key{ value: '\'', shifted: '\"'},
-        Key{ value: ',', shifted: '<'},
-        Key{ value: '.', shifted: '>'},
-        Key{ value: '/', shifted: '?'}
-    ];
-    add_remaining_keys(remaining_keys, &mut result);
+    add_remaining_keys(us_symbol_keys(), &mut result);
 
     connect_keyboard_nodes(qwerty_us, &mut result, KeyboardStyle::Slanted, false);
 
@@ -293,32 +1048,7 @@ pub fn generate_qwerty_uk() -> Keyboard {
                      \0 z x c v b n m , . /";
 
     add_alphabetics(&mut result);
-
-    let remaining_keys = vec![ 
-        Key{ value: '`', shifted: '¬'},
-        Key{ value: '1', shifted: '!'},
-        Key{ value: '2', shifted: '\"'},
-        Key{ value: '3', shifted: '£'},
-        Key{ value: '4', shifted: '$'},
-        Key{ value: '5', shifted: '%'},
-        Key{ value: '6', shifted: '^'},
-        Key{ value: '7', shifted: '&'},
-        Key{ value: '8', shifted: '*'},
-        Key{ value: '9', shifted: '('},
-        Key{ value: '0', shifted: ')'},
-        Key{ value: '-', shifted: '_'},
-        Key{ value: '=', shifted: '+'},
-        Key{ value: '[', shifted: '{'},
-        Key{ value: ']', shifted: '}'},
-        Key{ value: '\\', shifted: '|'},
-        Key{ value: ';', shifted: ':'},
-        Key{ value: '\'', shifted: '@'},
-        Key{ value: ',', shifted: '<'},
-        Key{ value: '.', shifted: '>'},
-        Key{ value: '/', shifted: '?'},
-        Key{ value: '#', shifted: '~'}
-    ];
-    add_remaining_keys(remaining_keys, &mut result);
+    add_remaining_keys(uk_symbol_keys(), &mut result);
 
     connect_keyboard_nodes(qwerty_uk, &mut result, KeyboardStyle::Slanted, false);
 
@@ -335,38 +1065,869 @@ pub fn generate_dvorak() -> Keyboard {
                       \0 a o e u i d h t n s -\n\
                       \0 ; q j k x b m w v z";
 
+    add_alphabetics(&mut result);
+    add_remaining_keys(us_symbol_keys(), &mut result);
+
+    connect_keyboard_nodes(qwerty_us, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the programmer dvorak keyboard layout. The number row
+/// is rearranged so symbols are unshifted and the digits need shift.
+pub fn generate_programmer_dvorak() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let dvorak = "$ & [ { } ( = * ) + ] ! #\n\
+                  \0 ; , . p y f g c r l / @ \\\n\
+                  \0 a o e u i d h t n s -\n\
+                  \0 ' q j k x b m w v z";
+
     add_alphabetics(&mut result);
 
-    let remaining_keys = vec![ 
-        Key{ value: '`', shifted: '~'},
-        Key{ value: '1', shifted: '!'},
-        Key{ value: '2', shifted: '@'},
-        Key{ value: '3', shifted: '#'},
-        Key{ value: '4', shifted: '$'},
-        Key{ value: '5', shifted: '%'},
-        Key{ value: '6', shifted: '^'},
-        Key{ value: '7', shifted: '&'},
-        Key{ value: '8', shifted: '*'},
-        Key{ value: '9', shifted: '('},
-        Key{ value: '0', shifted: ')'},
-        Key{ value: '-', shifted: '_'},
-        Key{ value: '=', shifted: '+'},
-        Key{ value: '[', shifted: '{'},
-        Key{ value: ']', shifted: '}'},
-        Key{ value: '\\', shifted: '|'},
-        Key{ value: ';', shifted: ':'},
-        Key{ value: '\'', shifted: '\"'},
-        Key{ value: ',', shifted: '<'},
-        Key{ value: '.', shifted: '>'},
-        Key{ value: '/', shifted: '?'}
+    let remaining_keys = vec![
+        Key::new('$', '~'),
+        Key::new('&', '%'),
+        Key::new('[', '7'),
+        Key::new('{', '5'),
+        Key::new('}', '3'),
+        Key::new('(', '1'),
+        Key::new('=', '9'),
+        Key::new('*', '0'),
+        Key::new(')', '2'),
+        Key::new('+', '4'),
+        Key::new(']', '6'),
+        Key::new('!', '8'),
+        Key::new('#', '`'),
+        Key::new(';', ':'),
+        Key::new(',', '<'),
+        Key::new('.', '>'),
+        Key::new('/', '?'),
+        Key::new('@', '^'),
+        Key::new('\\', '|'),
+        Key::new('-', '_'),
+        Key::new('\'', '\"')
     ];
     add_remaining_keys(remaining_keys, &mut result);
 
-    connect_keyboard_nodes(qwerty_us, &mut result, KeyboardStyle::Slanted, false);
+    connect_keyboard_nodes(dvorak, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the UK dvorak keyboard layout
+pub fn generate_dvorak_uk() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    // The ISO key left of ; means the bottom row has no offset
+    let dvorak = "` 1 2 3 4 5 6 7 8 9 0 [ ]\n\
+                  \0 ' , . p y f g c r l / =\n\
+                  \0 a o e u i d h t n s - #\n\
+                  \\ ; q j k x b m w v z";
+
+    add_alphabetics(&mut result);
+    add_remaining_keys(uk_symbol_keys(), &mut result);
+
+    connect_keyboard_nodes(dvorak, &mut result, KeyboardStyle::Slanted, false);
 
     result
 }
 
+/// Generates a graph for the left-handed dvorak keyboard layout
+pub fn generate_dvorak_left() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let dvorak = "` [ ] / p f m l j 4 3 2 1\n\
+                  \0 ; q b y u r s o . 6 5 = \\\n\
+                  \0 - k c d t h e a z 8 7\n\
+                  \0 ' x g v w n i , 0 9";
+
+    add_alphabetics(&mut result);
+    add_remaining_keys(us_symbol_keys(), &mut result);
+
+    connect_keyboard_nodes(dvorak, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the right-handed dvorak keyboard layout
+pub fn generate_dvorak_right() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let dvorak = "` 1 2 3 4 j l m f p / [ ]\n\
+                  \0 5 6 q . o r s u y b ; = \\\n\
+                  \0 7 8 z a e h t d c k -\n\
+                  \0 9 0 x , i n w v g '";
+
+    add_alphabetics(&mut result);
+    add_remaining_keys(us_symbol_keys(), &mut result);
+
+    connect_keyboard_nodes(dvorak, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the colemak keyboard layout
+pub fn generate_colemak() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let colemak = "` 1 2 3 4 5 6 7 8 9 0 - =\n\
+                   \0 q w f p g j l u y ; [ ] \\\n\
+                   \0 a r s t d h n e i o '\n\
+                   \0 z x c v b k m , . /";
+
+    add_alphabetics(&mut result);
+    add_remaining_keys(us_symbol_keys(), &mut result);
+
+    connect_keyboard_nodes(colemak, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the colemak-dh keyboard layout on an ANSI keyboard.
+/// The angle mod moves the bottom left row one key to the left with z taking
+/// the place of b.
+pub fn generate_colemak_dh() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let colemak_dh = "` 1 2 3 4 5 6 7 8 9 0 - =\n\
+                      \0 q w f p b j l u y ; [ ] \\\n\
+                      \0 a r s t g m n e i o '\n\
+                      \0 x c d v z k h , . /";
+
+    add_alphabetics(&mut result);
+    add_remaining_keys(us_symbol_keys(), &mut result);
+
+    connect_keyboard_nodes(colemak_dh, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the colemak-dh keyboard layout on an ISO (UK) 
+/// keyboard. The angle mod moves the bottom left row one key to the left so z
+/// sits on the extra ISO key and the backslash it displaces moves to where b
+/// would be.
+pub fn generate_colemak_dh_iso() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    // The ISO key left of z means the bottom row has no offset
+    let colemak_dh = "` 1 2 3 4 5 6 7 8 9 0 - =\n\
+                      \0 q w f p b j l u y ; [ ]\n\
+                      \0 a r s t g m n e i o ' #\n\
+                      z x c d v \\ k h , . /";
+
+    add_alphabetics(&mut result);
+    add_remaining_keys(uk_symbol_keys(), &mut result);
+
+    connect_keyboard_nodes(colemak_dh, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the workman keyboard layout
+pub fn generate_workman() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let workman = "` 1 2 3 4 5 6 7 8 9 0 - =\n\
+                   \0 q d r w b j f u p ; [ ] \\\n\
+                   \0 a s h t g y n e o i '\n\
+                   \0 z x m c v k l , . /";
+
+    add_alphabetics(&mut result);
+    add_remaining_keys(us_symbol_keys(), &mut result);
+
+    connect_keyboard_nodes(workman, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the russian ЙЦУКЕН keyboard layout
+pub fn generate_russian() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let russian = "ё 1 2 3 4 5 6 7 8 9 0 - =\n\
+                   \0 й ц у к е н г ш щ з х ъ \\\n\
+                   \0 ф ы в а п р о л д ж э\n\
+                   \0 я ч с м и т ь б ю .";
+
+    add_letters(RUSSIAN_ALPHABET, &mut result);
+    add_remaining_keys(cyrillic_symbol_keys(), &mut result);
+
+    connect_keyboard_nodes(russian, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the ukrainian keyboard layout
+pub fn generate_ukrainian() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let ukrainian = "' 1 2 3 4 5 6 7 8 9 0 - =\n\
+                     \0 й ц у к е н г ш щ з х ї ґ\n\
+                     \0 ф і в а п р о л д ж є\n\
+                     \0 я ч с м и т ь б ю .";
+
+    add_letters(UKRAINIAN_ALPHABET, &mut result);
+    // The apostrophe takes the place of ё and backslash is lost to ґ
+    let mut remaining_keys = cyrillic_symbol_keys();
+    remaining_keys.retain(|k| k.value != '\\');
+    remaining_keys.push(Key::new('\'', '₴'));
+    add_remaining_keys(remaining_keys, &mut result);
+
+    connect_keyboard_nodes(ukrainian, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the traditional bulgarian phonetic keyboard layout.
+/// Letters are placed on the latin key with the closest sound.
+pub fn generate_bulgarian_phonetic() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let bulgarian = "ч 1 2 3 4 5 6 7 8 9 0 - =\n\
+                     \0 я в е р т ъ у и о п ш щ ю\n\
+                     \0 а с д ф г х й к л ; '\n\
+                     \0 з ь ц ж б н м , . /";
+
+    add_letters(BULGARIAN_ALPHABET, &mut result);
+
+    let remaining_keys = vec![
+        Key::new('1', '!'),
+        Key::new('2', '@'),
+        Key::new('3', '№'),
+        Key::new('4', '$'),
+        Key::new('5', '%'),
+        Key::new('6', '€'),
+        Key::new('7', '§'),
+        Key::new('8', '*'),
+        Key::new('9', '('),
+        Key::new('0', ')'),
+        Key::new('-', '_'),
+        Key::new('=', '+'),
+        Key::new(';', ':'),
+        Key::new('\'', '\"'),
+        Key::new(',', '<'),
+        Key::new('.', '>'),
+        Key::new('/', '?')
+    ];
+    add_remaining_keys(remaining_keys, &mut result);
+
+    connect_keyboard_nodes(bulgarian, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Returns the number and symbol keys shared by the russian and ukrainian
+/// layouts.
+fn cyrillic_symbol_keys() -> Vec<Key> {
+    vec![
+        Key::new('1', '!'),
+        Key::new('2', '\"'),
+        Key::new('3', '№'),
+        Key::new('4', ';'),
+        Key::new('5', '%'),
+        Key::new('6', ':'),
+        Key::new('7', '?'),
+        Key::new('8', '*'),
+        Key::new('9', '('),
+        Key::new('0', ')'),
+        Key::new('-', '_'),
+        Key::new('=', '+'),
+        Key::new('\\', '/'),
+        Key::new('.', ',')
+    ]
+}
+
+/// Generates a graph for the greek keyboard layout
+pub fn generate_greek() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let greek = "` 1 2 3 4 5 6 7 8 9 0 - =\n\
+                 \0 ; ς ε ρ τ υ θ ι ο π [ ] \\\n\
+                 \0 α σ δ φ γ η ξ κ λ ΄ '\n\
+                 \0 ζ χ ψ ω β ν μ , . /";
+
+    add_letters(GREEK_ALPHABET, &mut result);
+    add_remaining_keys(us_symbol_keys(), &mut result);
+    // Final sigma has no uppercase, shift gives the dialytika tonos accent.
+    // The tonos key is a dead key for accented vowels.
+    add_shifted_pairs("ς΄", "΅¨", &mut result);
+
+    connect_keyboard_nodes(greek, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the standard hebrew (SI-1452) keyboard layout.
+/// Hebrew has no case, shifting a letter key gives the latin capital printed
+/// on it.
+pub fn generate_hebrew() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let hebrew = "; 1 2 3 4 5 6 7 8 9 0 - =\n\
+                  \0 / ' ק ר א ט ו ן ם פ ] [ \\\n\
+                  \0 ש ד ג כ ע י ח ל ך ף ,\n\
+                  \0 ז ס ב ה נ מ צ ת ץ .";
+
+    // Brackets are mirrored for right-to-left text
+    add_shifted_pairs(";1234567890-=", "~!@#$%^&*)(_+", &mut result);
+    add_shifted_pairs("/'קראטוןםפ][\\", "QWERTYUIOP}{|", &mut result);
+    add_shifted_pairs("שדגכעיחלךף,", "ASDFGHJKL:\"", &mut result);
+    add_shifted_pairs("זסבהנמצתץ.", "ZXCVBNM><?", &mut result);
+
+    connect_keyboard_nodes(hebrew, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the arabic (101) keyboard layout. Arabic has no case,
+/// shift gives diacritics, hamza forms and punctuation. Lam-alef ligatures are
+/// held as their isolated presentation form as one key types both letters.
+pub fn generate_arabic() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let arabic = "ذ 1 2 3 4 5 6 7 8 9 0 - =\n\
+                  \0 ض ص ث ق ف غ ع ه خ ح ج د \\\n\
+                  \0 ش س ي ب ل ا ت ن م ك ط\n\
+                  \0 ئ ء ؤ ر \u{FEFB} ى ة و ز ظ";
+
+    add_shifted_pairs("ذ1234567890-=", "\u{0651}!@#$%^&*)(_+", &mut result);
+    add_shifted_pairs("ضصثقفغعهخحجد\\", 
+                      "\u{064E}\u{064B}\u{064F}\u{064C}\u{FEF9}إ‘÷×؛<>|", 
+                      &mut result);
+    add_shifted_pairs("شسيبلاتنمكط", 
+                      "\u{0650}\u{064D}][\u{FEF7}أـ،/:\"", 
+                      &mut result);
+    add_shifted_pairs("ئءؤر\u{FEFB}ىةوزظ", 
+                      "~\u{0652}}{\u{FEF5}آ’,.؟", 
+                      &mut result);
+
+    connect_keyboard_nodes(arabic, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the japanese JIS (106/109 key) keyboard layout. Each
+/// key has its latin characters on the base layer and a kana layer above it
+/// used in kana input mode. The extra ¥ and ろ keys are included however the
+/// input method keys print nothing so are left out.
+///
+/// Voiced kana are typed as the unvoiced kana followed by the ゛ key, use
+/// `find_keys` to search for them.
+pub fn generate_jis() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    // The top left key switches input modes so is a void area
+    let jis = "\0 1 2 3 4 5 6 7 8 9 0 - ^ ¥\n\
+               \0 q w e r t y u i o p @ [\n\
+               \0 a s d f g h j k l ; : ]\n\
+               \0 z x c v b n m , . / \\";
+    let kana = "\0 ぬ ふ あ う え お や ゆ よ わ ほ へ ー\n\
+                \0 た て い す か ん な に ら せ ゛ ゜\n\
+                \0 ち と し は き く ま の り れ け む\n\
+                \0 つ さ そ ひ こ み も ね る め ろ";
+    let kana_shifted = "\0 \0 \0 ぁ ぅ ぇ ぉ ゃ ゅ ょ を \0 \0 \0\n\
+                        \0 \0 \0 ぃ \0 \0 \0 \0 \0 \0 \0 \0 「\n\
+                        \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 」\n\
+                        \0 っ \0 \0 \0 \0 \0 \0 、 。 ・ \0";
+
+    add_alphabetics(&mut result);
+    add_shifted_pairs("1234567890-^¥", "!\"#$%&'()\0=~|", &mut result);
+    add_shifted_pairs("@[;:],./\\", "`{+*}<>?_", &mut result);
+
+    connect_keyboard_nodes(jis, &mut result, KeyboardStyle::Slanted, false);
+    add_layer(jis, kana, kana_shifted, &mut result, KeyboardStyle::Slanted);
+
+    result
+}
+
+/// Generates a graph for the korean dubeolsik (2-set) keyboard layout. 
+/// Consonants are on the left hand and vowels on the right, shift gives the
+/// doubled consonants and a few extra vowels. 
+///
+/// Hangul is written as syllable blocks composed from jamo, use `find_keys` to
+/// search for syllables.
+pub fn generate_dubeolsik() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let dubeolsik = "` 1 2 3 4 5 6 7 8 9 0 - =\n\
+                     \0 ㅂ ㅈ ㄷ ㄱ ㅅ ㅛ ㅕ ㅑ ㅐ ㅔ [ ] \\\n\
+                     \0 ㅁ ㄴ ㅇ ㄹ ㅎ ㅗ ㅓ ㅏ ㅣ ; '\n\
+                     \0 ㅋ ㅌ ㅊ ㅍ ㅠ ㅜ ㅡ , . /";
+
+    add_remaining_keys(us_symbol_keys(), &mut result);
+    add_shifted_pairs("ㅂㅈㄷㄱㅅㅛㅕㅑㅐㅔ", "ㅃㅉㄸㄲㅆ\0\0\0ㅒㅖ", &mut result);
+    add_shifted_pairs("ㅁㄴㅇㄹㅎㅗㅓㅏㅣ", "\0\0\0\0\0\0\0\0\0", &mut result);
+    add_shifted_pairs("ㅋㅌㅊㅍㅠㅜㅡ", "\0\0\0\0\0\0\0", &mut result);
+
+    connect_keyboard_nodes(dubeolsik, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the InScript devanagari (hindi) keyboard layout.
+/// Vowel signs are typed after the consonant they attach to and some shifted
+/// number keys type a whole conjunct, use `find_clusters` to search text.
+pub fn generate_inscript() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let inscript = "ॊ 1 2 3 4 5 6 7 8 9 0 - ृ\n\
+                    \0 ौ ै ा ी ू ब ह ग द ज ड ़ ॉ\n\
+                    \0 ो े ् ि ु प र क त च ट\n\
+                    \0 ॆ ं म न व ल स , . य";
+
+    let keys = [
+        ("ॊ", "ऒ"), ("1", "ऍ"), ("2", "ॅ"), ("3", "्र"), ("4", "र्"),
+        ("5", "ज्ञ"), ("6", "त्र"), ("7", "क्ष"), ("8", "श्र"), ("9", "("),
+        ("0", ")"), ("-", "ः"), ("ृ", "ऋ"),
+        ("ौ", "औ"), ("ै", "ऐ"), ("ा", "आ"), ("ी", "ई"), ("ू", "ऊ"),
+        ("ब", "भ"), ("ह", "ङ"), ("ग", "घ"), ("द", "ध"), ("ज", "झ"),
+        ("ड", "ढ"), ("़", "ञ"), ("ॉ", "ऑ"),
+        ("ो", "ओ"), ("े", "ए"), ("्", "अ"), ("ि", "इ"), ("ु", "उ"),
+        ("प", "फ"), ("र", "ऱ"), ("क", "ख"), ("त", "थ"), ("च", "छ"),
+        ("ट", "ठ"),
+        ("ॆ", "ऎ"), ("ं", "ँ"), ("म", "ण"), ("न", "ऩ"), ("व", "ऴ"),
+        ("ल", "ळ"), ("स", "श"), (",", "ष"), (".", "।"), ("य", "\u{095F}"),
+    ];
+    add_output_pairs(&keys, &mut result);
+
+    connect_keyboard_nodes(inscript, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the thai kedmanee keyboard layout. Vowel and tone 
+/// marks are typed after the consonant they combine with.
+pub fn generate_kedmanee() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let kedmanee = "_ ๅ / - ภ ถ ุ ึ ค ต จ ข ช\n\
+                    \0 ๆ ไ ำ พ ะ ั ี ร น ย บ ล ฃ\n\
+                    \0 ฟ ห ก ด เ ้ ่ า ส ว ง\n\
+                    \0 ผ ป แ อ ิ ื ท ม ใ ฝ";
+
+    add_shifted_pairs("_ๅ/-ภถุึคตจขช", "%+๑๒๓๔ู฿๕๖๗๘๙", &mut result);
+    add_shifted_pairs("ๆไำพะัีรนยบลฃ", "๐\"ฎฑธํ๊ณฯญฐ,ฅ", &mut result);
+    add_shifted_pairs("ฟหกดเ้่าสวง", "ฤฆฏโฌ็๋ษศซ.", &mut result);
+    add_shifted_pairs("ผปแอิืทมใฝ", "()ฉฮฺ์?ฒฬฦ", &mut result);
+
+    connect_keyboard_nodes(kedmanee, &mut result, KeyboardStyle::Slanted, false);
+
+    result
+}
+
+/// Generates a graph for the standard zhuyin (bopomofo) keyboard layout used 
+/// in Taiwan. The zhuyin symbols and tone marks are on the base layer with the
+/// latin characters of the same key on a layer above, keys without a symbol
+/// keep their latin characters. The first tone is typed with space.
+pub fn generate_zhuyin() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let zhuyin = "` ㄅ ㄉ ˇ ˋ ㄓ ˊ ˙ ㄚ ㄞ ㄢ ㄦ =\n\
+                  \0 ㄆ ㄊ ㄍ ㄐ ㄔ ㄗ ㄧ ㄛ ㄟ ㄣ [ ] \\\n\
+                  \0 ㄇ ㄋ ㄎ ㄑ ㄕ ㄘ ㄨ ㄜ ㄠ ㄤ '\n\
+                  \0 ㄈ ㄌ ㄏ ㄒ ㄖ ㄙ ㄩ ㄝ ㄡ ㄥ";
+    let latin = "\0 1 2 3 4 5 6 7 8 9 0 - \0\n\
+                 \0 q w e r t y u i o p \0 \0 \0\n\
+                 \0 a s d f g h j k l ; \0\n\
+                 \0 z x c v b n m , . /";
+    let latin_shifted = "\0 ! @ # $ % ^ & * ( ) _ \0\n\
+                         \0 Q W E R T Y U I O P \0 \0 \0\n\
+                         \0 A S D F G H J K L : \0\n\
+                         \0 Z X C V B N M < > ?";
+
+    add_shifted_pairs("ㄅㄉˇˋㄓˊ˙ㄚㄞㄢㄦ", "\0\0\0\0\0\0\0\0\0\0\0", &mut result);
+    add_shifted_pairs("ㄆㄊㄍㄐㄔㄗㄧㄛㄟㄣ", "\0\0\0\0\0\0\0\0\0\0", &mut result);
+    add_shifted_pairs("ㄇㄋㄎㄑㄕㄘㄨㄜㄠㄤ", "\0\0\0\0\0\0\0\0\0\0", &mut result);
+    add_shifted_pairs("ㄈㄌㄏㄒㄖㄙㄩㄝㄡㄥ", "\0\0\0\0\0\0\0\0\0\0", &mut result);
+    add_shifted_pairs("`=[]\\'", "~+{}|\"", &mut result);
+
+    connect_keyboard_nodes(zhuyin, &mut result, KeyboardStyle::Slanted, false);
+    add_layer(zhuyin, latin, latin_shifted, &mut result, KeyboardStyle::Slanted);
+
+    result
+}
+
+/// Lays out the rows of a full ISO keyboard given without their offsets. The
+/// top row starts with the key left of 1, the home row ends with the key left
+/// of enter and the bottom row starts with the extra key right of left shift.
+/// Brazilian ABNT2 keyboards have another key left of right shift, making the
+/// bottom row 12 keys long.
+fn iso_template(keys: &str) -> String {
+    let rows = keys.lines().map(|row| row.trim()).collect::<Vec<_>>();
+    let lengths = rows.iter()
+                      .map(|row| row.split(' ').count())
+                      .collect::<Vec<_>>();
+    assert_eq!(lengths[..3], [13, 12, 12]);
+    assert!(lengths[3] == 11 || lengths[3] == 12);
+
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| if i == 1 || i == 2 {
+            format!("\0 {}", row)
+        } else {
+            row.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns a keyboard string with the same shape as `keys` where every key
+/// is \0, for layers with nothing on their shifted level.
+fn blank_template(keys: &str) -> String {
+    keys.chars()
+        .map(|c| if c == ' ' || c == '\n' { c } else { '\0' })
+        .collect()
+}
+
+/// Generates a graph for a layout on the ISO keyboard (see `iso_template`).
+/// The base and shifted strings give the two levels of every key and the 
+/// AltGr strings the third and fourth levels, which are added as a layer. If
+/// AltGr and shift gives nothing on every key it can be left as None. Dead
+/// keys are represented by their spacing characters (see `deadkey`).
+fn generate_iso_layout(base: &str,
+                       shifted: &str,
+                       altgr: &str,
+                       altgr_shifted: Option<&str>) -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+
+    add_key_grid(base, shifted, &mut result);
+    connect_iso_layout(base, altgr, altgr_shifted, &mut result);
+
+    result
+}
+
+/// Connects the keys already in the graph in the layout given by the base 
+/// string on the ISO keyboard and adds the AltGr levels as a layer (see
+/// `generate_iso_layout`).
+fn connect_iso_layout(base: &str,
+                      altgr: &str,
+                      altgr_shifted: Option<&str>,
+                      graph: &mut Keyboard) {
+    let base = iso_template(base);
+    let altgr = iso_template(altgr);
+    let altgr_shifted = match altgr_shifted {
+        Some(keys) => iso_template(keys),
+        None => blank_template(&altgr),
+    };
+
+    connect_keyboard_nodes(&base, graph, KeyboardStyle::Slanted, false);
+    add_layer(&base, &altgr, &altgr_shifted, graph, KeyboardStyle::Slanted);
+}
+
+/// Generates a graph for the swedish keyboard layout, the finnish layout is
+/// identical.
+pub fn generate_swedish() -> Keyboard {
+    generate_iso_layout("§ 1 2 3 4 5 6 7 8 9 0 + ´\n\
+                         q w e r t y u i o p å ¨\n\
+                         a s d f g h j k l ö ä '\n\
+                         < z x c v b n m , . -",
+                        "½ ! \" # ¤ % & / ( ) = ? `\n\
+                         Q W E R T Y U I O P Å ^\n\
+                         A S D F G H J K L Ö Ä *\n\
+                         > Z X C V B N M ; : _",
+                        "\0 \0 @ £ $ \0 \0 { [ ] } \\ \0\n\
+                         \0 \0 € \0 \0 \0 \0 \0 \0 \0 \0 ~\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                         | \0 \0 \0 \0 \0 \0 µ \0 \0 \0",
+                        None)
+}
+
+/// Generates a graph for the finnish keyboard layout.
+pub fn generate_finnish() -> Keyboard {
+    generate_swedish()
+}
+
+/// Generates a graph for the norwegian keyboard layout
+pub fn generate_norwegian() -> Keyboard {
+    generate_iso_layout("| 1 2 3 4 5 6 7 8 9 0 + \\\n\
+                         q w e r t y u i o p å ¨\n\
+                         a s d f g h j k l ø æ '\n\
+                         < z x c v b n m , . -",
+                        "§ ! \" # ¤ % & / ( ) = ? `\n\
+                         Q W E R T Y U I O P Å ^\n\
+                         A S D F G H J K L Ø Æ *\n\
+                         > Z X C V B N M ; : _",
+                        "\0 \0 @ £ $ \0 \0 { [ ] } \0 ´\n\
+                         \0 \0 € \0 \0 \0 \0 \0 \0 \0 \0 ~\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                         \0 \0 \0 \0 \0 \0 \0 µ \0 \0 \0",
+                        None)
+}
+
+/// Generates a graph for the danish keyboard layout
+pub fn generate_danish() -> Keyboard {
+    generate_iso_layout("½ 1 2 3 4 5 6 7 8 9 0 + ´\n\
+                         q w e r t y u i o p å ¨\n\
+                         a s d f g h j k l æ ø '\n\
+                         < z x c v b n m , . -",
+                        "§ ! \" # ¤ % & / ( ) = ? `\n\
+                         Q W E R T Y U I O P Å ^\n\
+                         A S D F G H J K L Æ Ø *\n\
+                         > Z X C V B N M ; : _",
+                        "\0 \0 @ £ $ \0 \0 { [ ] } \0 |\n\
+                         \0 \0 € \0 \0 \0 \0 \0 \0 \0 \0 ~\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                         \\ \0 \0 \0 \0 \0 \0 µ \0 \0 \0",
+                        None)
+}
+
+/// Generates a graph for the icelandic keyboard layout. The key right of æ
+/// is a dead acute accent with nothing on its shifted level.
+pub fn generate_icelandic() -> Keyboard {
+    generate_iso_layout("° 1 2 3 4 5 6 7 8 9 0 ö -\n\
+                         q w e r t y u i o p ð '\n\
+                         a s d f g h j k l æ ´ +\n\
+                         < z x c v b n m , . þ",
+                        "¨ ! \" # $ % & / ( ) = Ö _\n\
+                         Q W E R T Y U I O P Ð ?\n\
+                         A S D F G H J K L Æ \0 *\n\
+                         > Z X C V B N M ; : Þ",
+                        "\0 \0 \0 \0 \0 \0 \0 { [ ] } \\ \0\n\
+                         @ \0 € \0 \0 \0 \0 \0 \0 \0 \0 ~\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 ^ `\n\
+                         | \0 \0 \0 \0 \0 \0 µ \0 \0 \0",
+                        None)
+}
+
+/// Generates a graph for the spanish keyboard layout
+pub fn generate_spanish() -> Keyboard {
+    generate_iso_layout("º 1 2 3 4 5 6 7 8 9 0 ' ¡\n\
+                         q w e r t y u i o p ` +\n\
+                         a s d f g h j k l ñ ´ ç\n\
+                         < z x c v b n m , . -",
+                        "ª ! \" · $ % & / ( ) = ? ¿\n\
+                         Q W E R T Y U I O P ^ *\n\
+                         A S D F G H J K L Ñ ¨ Ç\n\
+                         > Z X C V B N M ; : _",
+                        "\\ | @ # ~ \0 ¬ \0 \0 \0 \0 \0 \0\n\
+                         \0 \0 € \0 \0 \0 \0 \0 \0 \0 [ ]\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 { }\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0",
+                        None)
+}
+
+/// Generates a graph for the latin american spanish keyboard layout
+pub fn generate_latin_american() -> Keyboard {
+    generate_iso_layout("| 1 2 3 4 5 6 7 8 9 0 ' ¿\n\
+                         q w e r t y u i o p ´ +\n\
+                         a s d f g h j k l ñ { }\n\
+                         < z x c v b n m , . -",
+                        "° ! \" # $ % & / ( ) = ? ¡\n\
+                         Q W E R T Y U I O P ¨ *\n\
+                         A S D F G H J K L Ñ [ ]\n\
+                         > Z X C V B N M ; : _",
+                        "¬ \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \\ \0\n\
+                         @ \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 ~\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 ^ `\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0",
+                        None)
+}
+
+/// Generates a graph for the italian keyboard layout. Italian keyboards have
+/// no dead keys, the brackets are typed with AltGr and AltGr + shift.
+pub fn generate_italian() -> Keyboard {
+    generate_iso_layout("\\ 1 2 3 4 5 6 7 8 9 0 ' ì\n\
+                         q w e r t y u i o p è +\n\
+                         a s d f g h j k l ò à ù\n\
+                         < z x c v b n m , . -",
+                        "| ! \" £ $ % & / ( ) = ? ^\n\
+                         Q W E R T Y U I O P é *\n\
+                         A S D F G H J K L ç ° §\n\
+                         > Z X C V B N M ; : _",
+                        "\0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                         \0 \0 € \0 \0 \0 \0 \0 \0 \0 [ ]\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 @ # \0\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0",
+                        Some("\0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                              \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 { }\n\
+                              \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                              \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0"))
+}
+
+/// Generates a graph for the portuguese keyboard layout
+pub fn generate_portuguese() -> Keyboard {
+    generate_iso_layout("\\ 1 2 3 4 5 6 7 8 9 0 ' «\n\
+                         q w e r t y u i o p + ´\n\
+                         a s d f g h j k l ç º ~\n\
+                         < z x c v b n m , . -",
+                        "| ! \" # $ % & / ( ) = ? »\n\
+                         Q W E R T Y U I O P * `\n\
+                         A S D F G H J K L Ç ª ^\n\
+                         > Z X C V B N M ; : _",
+                        "\0 \0 @ £ § \0 \0 { [ ] } \0 \0\n\
+                         \0 \0 € \0 \0 \0 \0 \0 \0 \0 ¨ \0\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0",
+                        None)
+}
+
+/// Generates a graph for the brazilian ABNT2 keyboard layout, which has an
+/// extra key for / between the ; key and right shift.
+pub fn generate_abnt2() -> Keyboard {
+    generate_iso_layout("' 1 2 3 4 5 6 7 8 9 0 - =\n\
+                         q w e r t y u i o p ´ [\n\
+                         a s d f g h j k l ç ~ ]\n\
+                         \\ z x c v b n m , . ; /",
+                        "\" ! @ # $ % ¨ & * ( ) _ +\n\
+                         Q W E R T Y U I O P ` {\n\
+                         A S D F G H J K L Ç ^ }\n\
+                         | Z X C V B N M < > : ?",
+                        "\0 ¹ ² ³ £ ¢ ¬ \0 \0 \0 \0 \0 §\n\
+                         \0 \0 ° \0 \0 \0 \0 \0 \0 \0 \0 ª\n\
+                         \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 º\n\
+                         \0 \0 \0 ₢ \0 \0 \0 \0 \0 \0 \0 \0",
+                        None)
+}
+
+/// Generates a graph for the turkish Q keyboard layout. The dotted and dotless
+/// i are separate keys using turkish case mapping.
+pub fn generate_turkish_q() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+
+    add_cased_letters(TURKISH_ALPHABET, Casing::Turkic, &mut result);
+    add_shifted_pairs("\"1234567890*-,<.", "é!'^+%&/()=?_;>:", &mut result);
+
+    connect_iso_layout("\" 1 2 3 4 5 6 7 8 9 0 * -\n\
+                        q w e r t y u ı o p ğ ü\n\
+                        a s d f g h j k l ş i ,\n\
+                        < z x c v b n m ö ç .",
+                       "\0 \0 £ # $ ½ \0 { [ ] } \\ |\n\
+                        @ \0 € \0 \0 \0 \0 \0 \0 \0 ¨ ~\n\
+                        \0 \0 \0 \0 \0 \0 \0 \0 \0 ´ \0 `\n\
+                        \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0",
+                       None,
+                       &mut result);
+
+    result
+}
+
+/// Generates a graph for the turkish F keyboard layout, which places letters
+/// by their frequency in turkish.
+pub fn generate_turkish_f() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+
+    add_cased_letters(TURKISH_ALPHABET, Casing::Turkic, &mut result);
+    add_shifted_pairs("+1234567890/-<.,", "*!\"^$%&'()=?_>:;", &mut result);
+
+    connect_iso_layout("+ 1 2 3 4 5 6 7 8 9 0 / -\n\
+                        f g ğ ı o d r n h p q w\n\
+                        u i e a ü t k m l y ş x\n\
+                        < j ö v c ç z s b . ,",
+                       "¬ ¹ ² # ¼ ½ ¾ { [ ] } \\ |\n\
+                        @ \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                        \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                        \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0",
+                       None,
+                       &mut result);
+
+    result
+}
+
+/// Generates a graph for the french BÉPO keyboard layout. Layer 1 is reached
+/// with AltGr.
+pub fn generate_bepo() -> Keyboard {
+    generate_iso_layout("$ \" « » ( ) @ + - / * = %\n\
+                         b é p o è ^ v d l j z w\n\
+                         a u i e , c t s r n m ç\n\
+                         ê à y x . k ' q g h f",
+                        "# 1 2 3 4 5 6 7 8 9 0 ° `\n\
+                         B É P O È ! V D L J Z W\n\
+                         A U I E ; C T S R N M Ç\n\
+                         Ê À Y X : K ? Q G H F",
+                        "– — < > [ ] ^ ± − ÷ × ≠ ‰\n\
+                         | ´ & œ ` ¡ ˇ ð \0 ĳ ə ˘\n\
+                         æ ù ¨ € \0 © þ ß ® \0 ħ \0\n\
+                         / \\ { } … ~ ¿ ˚ µ † ˛",
+                        Some("¶ „ “ ” ≤ ≥ \0 ¬ ¼ ½ ¾ ′ ″\n\
+                              ¦ ˝ § Œ \0 \0 \0 Ð \0 Ĳ Ə \0\n\
+                              Æ Ù ˙ ¤ \0 ſ Þ ẞ ™ \0 Ħ \0\n\
+                              \0 \0 ‘ ’ · \0 \0 \0 \0 ‡ ª"))
+}
+
+/// Generates a graph for the german Neo2 keyboard layout. Neo2 has six levels
+/// which are on layers as follows:
+///
+/// * Layer 0 - levels 1 and 2, no modifier and shift
+/// * Layer 1 - levels 3 and 5, Mod3 (symbols) and Mod3 + shift (greek)
+/// * Layer 2 - level 6, Mod3 + Mod4 (mathematical symbols)
+/// * Layer 3 - level 4, Mod4 (numpad, the navigation keys print nothing)
+///
+/// The Mod3 key left of enter and the Mod4 key right of left shift are void
+/// areas.
+pub fn generate_neo2() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let neo = iso_template("^ 1 2 3 4 5 6 7 8 9 0 - `\n\
+                            x v l c w k h g f q ß ´\n\
+                            u i a e o s n r t d y \0\n\
+                            \0 ü ö ä p z b m , . j");
+    let neo_shifted = iso_template("ˇ ° § ℓ » « $ € „ “ ” — ¸\n\
+                                    X V L C W K H G F Q ẞ ˜\n\
+                                    U I A E O S N R T D Y \0\n\
+                                    \0 Ü Ö Ä P Z B M – • J");
+    let mod3 = iso_template("\0 ¹ ² ³ › ‹ ¢ ¥ ‚ ‘ ’ \0 \0\n\
+                             … _ [ ] ^ ! < > = & ſ \0\n\
+                             \\ / { } * ? ( ) - : @ \0\n\
+                             \0 # $ | ~ ` + % \" ' ;");
+    let mod3_shifted = iso_template("\0 ₁ ₂ ₃ ♀ ♂ ⚥ ϰ ⟨ ⟩ ₀ \0 \0\n\
+                                     ξ \0 λ χ ω κ ψ γ φ ϕ ς \0\n\
+                                     \0 ι α ε ο σ ν ρ τ δ υ \0\n\
+                                     \0 \0 ϵ η π ζ β μ ϱ ϑ θ");
+    let mod3_mod4 = iso_template("\0 ¬ ∨ ∧ ⊥ ∡ ∥ → ∞ ∝ ∅ \0 \0\n\
+                                  Ξ √ Λ ℂ Ω × Ψ Γ Φ ℚ ∘ \0\n\
+                                  ⊂ ∫ ∀ ∃ ∈ Σ ℕ ℝ ∂ Δ ∇ \0\n\
+                                  \0 ∪ ∩ ℵ Π ℤ ⇐ ⇔ ⇒ ↦ Θ");
+    let mod4 = iso_template("\0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0 \0\n\
+                             \0 \0 \0 \0 \0 ¡ 7 8 9 + − \0\n\
+                             \0 \0 \0 \0 \0 ¿ 4 5 6 , . \0\n\
+                             \0 \0 \0 \0 \0 \0 : 1 2 3 ;");
+
+    add_key_grid(&neo, &neo_shifted, &mut result);
+
+    connect_keyboard_nodes(&neo, &mut result, KeyboardStyle::Slanted, false);
+    let layer = add_layer(&neo, &mod3, &mod3_shifted, &mut result, KeyboardStyle::Slanted);
+    let layer = add_layer_above(&layer,
+                                &mod3_mod4,
+                                &blank_template(&mod3_mod4),
+                                &mut result,
+                                KeyboardStyle::Slanted);
+    add_layer_above(&layer, &mod4, &blank_template(&mod4), &mut result, KeyboardStyle::Slanted);
+
+    result
+}
+
+/// Generates a graph for a touchscreen keyboard with a page of letters and a
+/// page of symbols. Shift on the symbols page switches to a second page of
+/// symbols which is given as the shifted values. Rows of the symbols page 
+/// start at the offsets given in key widths.
+fn generate_mobile(symbols: &str, symbols_shifted: &str, offsets: &[f32]) -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    // Shift is left of z and backspace right of m, these print nothing
+    let letters = "q w e r t y u i o p\n\
+                   a s d f g h j k l\n\
+                   z x c v b n m";
+    let letter_offsets = [0.0, 0.5, 1.5];
+
+    add_alphabetics(&mut result);
+    let letter_keys = keyboard_rows(letters).iter()
+        .map(|row| row.iter().map(|c| result.find_key(*c)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    connect_offset_rows(&letter_keys, &letter_offsets, &mut result);
+    add_offset_page(&letter_keys, &letter_offsets, symbols, symbols_shifted, offsets, &mut result);
+    add_long_press(MOBILE_LONG_PRESS, &mut result);
+
+    result
+}
+
+/// Generates a graph for the iOS touchscreen keyboard. The letters are on 
+/// layer 0 and the "123" symbols page on layer 1, the "#+=" page is reached
+/// with shift from the symbols page. Long pressed characters are alternates of
+/// the key pressed along with the keys in the same place on the other pages,
+/// use the `mobile` module to tell them apart and find the page of a key.
+pub fn generate_ios() -> Keyboard {
+    generate_mobile("1 2 3 4 5 6 7 8 9 0\n\
+                     - / : ; ( ) $ & @ \"\n\
+                     . , ? ! '",
+                    "[ ] { } # % ^ * + =\n\
+                     _ \\ | ~ < > € £ ¥ •\n\
+                     \0 \0 \0 \0 \0",
+                    &[0.0, 0.0, 1.5])
+}
+
+/// Generates a graph for the android (Gboard) touchscreen keyboard. The
+/// letters are on layer 0 and the "?123" symbols page on layer 1, the "=\\<" 
+/// page is reached with shift from the symbols page.
+pub fn generate_android() -> Keyboard {
+    generate_mobile("1 2 3 4 5 6 7 8 9 0\n\
+                     @ # $ _ & - + ( ) /\n\
+                     * \" ' : ; ! ?",
+                    "~ ` | • √ π ÷ × ¶ ∆\n\
+                     £ ¢ € ¥ ^ ° = { } \\\n\
+                     % © ® ™ ✓ [ ]",
+                    &[0.0, 0.0, 1.5])
+}
+
 /// Generates a standard numpad.
 pub fn generate_standard_numpad() -> Keyboard {
     let mut result = DiGraphMap::<Key, Edge>::new();
@@ -379,6 +1940,19 @@ pub fn generate_standard_numpad() -> Keyboard {
 }
 
 
+/// Generates the phone keypad (ITU E.161). The digits are laid out from 1 at
+/// the top left, the opposite way up to a numpad. Letters printed on the keys
+/// are typed with T9 or multi-tap input, see the `t9` module.
+pub fn generate_phone_keypad() -> Keyboard {
+    let mut result = DiGraphMap::<Key, Edge>::new();
+    let keypad = "1 2 3\n4 5 6\n7 8 9\n* 0 #";
+
+    add_unshifted_number_keys(&mut result);
+
+    connect_keyboard_nodes(keypad, &mut result, KeyboardStyle::Aligned, true);
+    result
+}
+
 /// Generates the Apple Mac style numpad
 pub fn generate_mac_numpad() -> Keyboard {
     let mut result = DiGraphMap::<Key, Edge>::new();
@@ -401,10 +1975,7 @@ mod tests {
 
         let uppercase = ALPHABET.to_uppercase();
         for (l, u) in ALPHABET.chars().zip(uppercase.chars()) {
-            let test = Key {
-                value: l,
-                shifted: u
-            };
+            let test = Key::new(l, u);
             assert!(result.contains_node(test));
             // Get testing of trait for free
             assert!(result.find_key(l).is_some());
@@ -412,6 +1983,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cyrillic_letters() {
+        let mut result = DiGraphMap::<Key, Edge>::new();
+        add_letters(RUSSIAN_ALPHABET, &mut result);
+        assert_eq!(result.node_count(), 33);
+
+        let capitals = RUSSIAN_ALPHABET.to_uppercase();
+        for (l, u) in RUSSIAN_ALPHABET.chars().zip(capitals.chars()) {
+            assert!(result.contains_node(Key::new(l, u)));
+        }
+        assert_eq!(result.find_key('Ё'), Some(Key::new('ё', 'Ё')));
+        assert_eq!(uppercase('ß'), '\0');
+        assert_eq!(uppercase('ς'), '\0');
+        assert_eq!(uppercase('σ'), 'Σ');
+    }
+
+    #[test]
+    fn test_turkic_casing() {
+        assert_eq!(Casing::Default.uppercase('i'), 'I');
+        assert_eq!(Casing::Default.uppercase('ı'), '\0');
+        assert_eq!(Casing::Turkic.uppercase('i'), 'İ');
+        assert_eq!(Casing::Turkic.uppercase('ı'), 'I');
+        assert_eq!(Casing::Turkic.uppercase('ş'), 'Ş');
+
+        let mut result = DiGraphMap::<Key, Edge>::new();
+        add_cased_letters(TURKISH_ALPHABET, Casing::Turkic, &mut result);
+        assert_eq!(result.node_count(), 32);
+        assert!(result.nodes().all(|k| k.shifted != '\0'));
+    }
+
     #[test]
     fn test_add_number_keys() {
         assert_eq!(NUMBERS.chars().count(), 10);
@@ -419,10 +2020,7 @@ mod tests {
         let mut result = DiGraphMap::<Key, Edge>::new();
         add_unshifted_number_keys(&mut result);
         for c in NUMBERS.chars() {
-            let test = Key {
-                value: c,
-                shifted: '\0'
-            };
+            let test = Key::new(c, '\0');
             assert!(result.contains_node(test));
             assert!(result.find_key(c).is_some());
         }
@@ -431,10 +2029,7 @@ mod tests {
 
     #[test]
     fn is_shifted_test() {
-        let t = Key { 
-            value: 'a',
-            shifted: 'A'
-        };
+        let t = Key::new('a', 'A');
         assert!(t.is_shifted('A'));
         assert!(t.is_unshifted('a'));
         assert!(!t.is_shifted('a'));
@@ -0,0 +1,128 @@
+//! Queries for touchscreen keyboards (see `generate_ios` and 
+//! `generate_android`).
+//!
+//! Touchscreen keyboards show one page of keys at a time and switching page
+//! takes an extra tap, so each page is a layer of the graph. Characters typed
+//! by long pressing a key are also alternates of that key but are on the same
+//! page as it, they're joined to it by same key edges both ways.
+
+use petgraph::Direction::Outgoing;
+use ::*;
+
+/// Returns the key long pressed to type a key, or None if the key isn't typed
+/// by a long press.
+pub fn pressed_key(graph: &Keyboard, key: Key) -> Option<Key> {
+    let same_key = |a, b| graph.edge_weight(a, b).is_some_and(|e| e.is_same_key());
+    let mut neighbours = graph.neighbors_directed(key, Outgoing);
+    match (neighbours.next(), neighbours.next()) {
+        (Some(n), None) if same_key(key, n) && same_key(n, key) => Some(n),
+        _ => None,
+    }
+}
+
+/// Returns true if the key is typed by long pressing another key.
+pub fn is_long_press(graph: &Keyboard, key: Key) -> bool {
+    pressed_key(graph, key).is_some()
+}
+
+/// Returns the keys typed by long pressing a key. Unlike 
+/// `KeySearch::find_alternates` this leaves out the keys in the same place on
+/// other pages.
+pub fn long_press_alternates(graph: &Keyboard, key: Key) -> Vec<Key> {
+    graph.neighbors_directed(key, Outgoing)
+         .filter(|n| pressed_key(graph, *n) == Some(key))
+         .collect()
+}
+
+/// Returns the page a key is on, 0 for the first page shown. Long pressed keys
+/// are on the page of the key pressed.
+pub fn page(graph: &Keyboard, key: Key) -> usize {
+    graph.find_layer(key)
+}
+
+/// Counts the page switches to type some text starting from the first page.
+/// Each switch is a tap on the page key so a user typing a symbol in a word 
+/// switches twice. Returns None if any of the text can't be typed.
+pub fn page_switches(graph: &Keyboard, text: &str) -> Option<usize> {
+    let keys = graph.find_keys(text)
+                    .into_iter()
+                    .collect::<Option<Vec<_>>>()?;
+    let mut current = 0;
+    let mut switches = 0;
+    for k in keys {
+        let p = page(graph, k);
+        if p != current {
+            switches += 1;
+            current = p;
+        }
+    }
+    Some(switches)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use mobile::*;
+
+    #[test]
+    fn test_pages() {
+        let ios = generate_ios();
+        let e = ios.find_key('e').unwrap();
+        let acute = ios.find_key('é').unwrap();
+        assert!(is_long_press(&ios, acute));
+        assert!(!is_long_press(&ios, e));
+        assert_eq!(page(&ios, acute), 0);
+        assert_eq!(page(&ios, ios.find_key('7').unwrap()), 1);
+        assert_eq!(page(&ios, ios.find_key('€').unwrap()), 1);
+        assert_eq!(long_press_alternates(&ios, e).len(), 7);
+        let three = ios.find_key('3').unwrap();
+        assert!(!long_press_alternates(&ios, e).contains(&three));
+        assert!(ios.find_alternates(e).contains(&three));
+
+        assert_eq!(page_switches(&ios, "café"), Some(0));
+        assert_eq!(page_switches(&ios, "pass123"), Some(1));
+        assert_eq!(page_switches(&ios, "a1b2"), Some(3));
+        assert_eq!(page_switches(&ios, "a\u{1F600}"), None);
+    }
+
+    #[test]
+    fn test_positions() {
+        use std::collections::HashMap;
+
+        for keyboard in [generate_ios(), generate_android()].iter() {
+            // Every key on a page has a place of its own
+            let grid = geometry::grid_positions(keyboard);
+            let mut seen = HashMap::new();
+            for (k, p) in grid.iter() {
+                assert_eq!(seen.insert((*p, page(keyboard, *k)), *k), None);
+            }
+            let placed = keyboard.nodes().filter(|k| !is_long_press(keyboard, *k)).count();
+            assert_eq!(grid.len(), placed);
+
+            // Long pressed keys stay on the page of the key pressed
+            let acute = keyboard.find_key('é').unwrap();
+            assert_eq!(keyboard.find_layer(acute), 0);
+            assert!(!grid.contains_key(&acute));
+            let dash = keyboard.find_key('–').unwrap();
+            assert_eq!(keyboard.find_layer(dash), 1);
+            assert_eq!(pressed_key(keyboard, dash), keyboard.find_key('-'));
+        }
+
+        let ios = generate_ios();
+        let grid = geometry::grid_positions(&ios);
+        let find = |c| grid[&ios.find_key(c).unwrap()];
+        assert_eq!(find('2'), (1, 0));
+        assert_eq!(find('3'), (2, 0));
+        assert_eq!(find('4'), (3, 0));
+        assert_eq!(geometry::hand(&grid, ios.find_key('q').unwrap()),
+                   Some(geometry::Hand::Left));
+        assert_eq!(geometry::hand(&grid, ios.find_key('p').unwrap()),
+                   Some(geometry::Hand::Right));
+
+        // Pages line up between the two keyboards
+        let android = generate_android();
+        assert_eq!(convert::convert(&ios, &android, "pass123"), "pass123");
+        assert_eq!(convert::convert(&ios, &android, "$&"), "+(");
+        assert_eq!(convert::convert(&ios, &android, "café"), "café");
+    }
+}
@@ -0,0 +1,272 @@
+//! Pattern analysis of numeric codes typed on a keypad, such as
+//! `STANDARD_NUMPAD`, `MAC_NUMPAD` or `PHONE_KEYPAD`.
+//!
+//! PINs are often chosen for the shape they trace rather than the digits, so
+//! a code is classified by its shape on the keypad. The guesses estimate
+//! assumes an attacker tries every code of the more likely pattern classes 
+//! before moving on to the next.
+
+use std::collections::HashMap;
+use geometry;
+use ::*;
+
+/// Shapes a PIN can trace on a keypad, from the most to the least likely
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum PinPattern {
+    /// A single key pressed repeatedly, i.e. 0000
+    Repeated,
+    /// Three or more digits counting up or down by one, i.e. 1234 or 7890
+    Sequence,
+    /// Keys on a single row, i.e. 123 or 4566
+    Row,
+    /// Keys in a single column, i.e. 2580
+    Column,
+    /// Keys on a single diagonal, i.e. 159 or 3573
+    Diagonal,
+    /// Keys at the corners of the block of 1 to 9, i.e. 1397
+    Corners,
+    /// Keys in a cross around the centre of the block of 1 to 9, i.e. 2846
+    Cross,
+    /// The second half traces the same shape as the first, i.e. 1245
+    RepeatedShape,
+    /// Each key is next to or the same as the key before it, i.e. 1478
+    Walk,
+    /// None of the above
+    Random,
+}
+
+/// Pattern classes in the order an attacker is assumed to try them
+static PATTERN_ORDER: [PinPattern; 10] = [
+    PinPattern::Repeated,
+    PinPattern::Sequence,
+    PinPattern::Row,
+    PinPattern::Column,
+    PinPattern::Diagonal,
+    PinPattern::Corners,
+    PinPattern::Cross,
+    PinPattern::RepeatedShape,
+    PinPattern::Walk,
+    PinPattern::Random,
+];
+
+/// Result of analysing a PIN
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PinAnalysis {
+    /// Shape traced by the PIN
+    pub pattern: PinPattern,
+    /// Estimated number of guesses to find the PIN
+    pub guesses: f64,
+}
+
+/// Analyses a PIN typed on a keypad. Returns None if the PIN is empty or has 
+/// anything other than digits on the keypad.
+pub fn analyse(keypad: &Keyboard, pin: &str) -> Option<PinAnalysis> {
+    if pin.is_empty() || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let keys = pin.chars()
+                  .map(|c| keypad.find_key(c))
+                  .collect::<Option<Vec<_>>>()?;
+    let positions = geometry::grid_positions(keypad);
+    let pattern = classify(keypad, &positions, &keys);
+
+    let length = keys.len();
+    let earlier = PATTERN_ORDER.iter()
+                               .take_while(|p| **p != pattern)
+                               .map(|p| class_size(keypad, &positions, *p, length))
+                               .sum::<f64>();
+    let guesses = earlier + class_size(keypad, &positions, pattern, length) / 2.0;
+
+    Some(PinAnalysis {
+        pattern,
+        guesses: guesses.max(1.0),
+    })
+}
+
+/// Returns the positions of the digit keys
+fn digit_positions(positions: &HashMap<Key, (i32, i32)>) -> Vec<(char, (i32, i32))> {
+    positions.iter()
+             .filter_map(|(k, p)| k.value.as_char().map(|c| (c, *p)))
+             .filter(|&(c, _)| c.is_ascii_digit())
+             .collect()
+}
+
+/// Returns the top left and bottom right of the block of keys 1 to 9
+fn block_bounds(positions: &HashMap<Key, (i32, i32)>) -> ((i32, i32), (i32, i32)) {
+    let block = digit_positions(positions).into_iter()
+                                          .filter(|&(c, _)| c != '0')
+                                          .map(|(_, p)| p)
+                                          .collect::<Vec<_>>();
+    let min_x = block.iter().map(|p| p.0).min().unwrap_or(0);
+    let min_y = block.iter().map(|p| p.1).min().unwrap_or(0);
+    let max_x = block.iter().map(|p| p.0).max().unwrap_or(0);
+    let max_y = block.iter().map(|p| p.1).max().unwrap_or(0);
+    ((min_x, min_y), (max_x, max_y))
+}
+
+fn classify(keypad: &Keyboard,
+            positions: &HashMap<Key, (i32, i32)>,
+            keys: &[Key]) -> PinPattern {
+
+    let points = keys.iter().map(|k| positions[k]).collect::<Vec<_>>();
+    let mut distinct = points.clone();
+    distinct.sort();
+    distinct.dedup();
+    let (x0, y0) = points[0];
+    let ((min_x, min_y), (max_x, max_y)) = block_bounds(positions);
+    let corners = [(min_x, min_y), (max_x, min_y), (min_x, max_y), (max_x, max_y)];
+    let centre = ((min_x + max_x) / 2, (min_y + max_y) / 2);
+
+    if distinct.len() == 1 {
+        PinPattern::Repeated
+    } else if is_sequence(keys) {
+        PinPattern::Sequence
+    } else if points.iter().all(|p| p.1 == y0) {
+        PinPattern::Row
+    } else if points.iter().all(|p| p.0 == x0) {
+        PinPattern::Column
+    } else if points.iter().all(|p| p.0 - x0 == p.1 - y0) ||
+              points.iter().all(|p| p.0 - x0 == y0 - p.1) {
+        PinPattern::Diagonal
+    } else if points.iter().all(|p| corners.contains(p)) {
+        PinPattern::Corners
+    } else if distinct.len() >= 3 &&
+              points.iter().all(|p| (p.0 - centre.0).abs() + (p.1 - centre.1).abs() <= 1) {
+        PinPattern::Cross
+    } else if is_repeated_shape(&points) {
+        PinPattern::RepeatedShape
+    } else if keys.windows(2).all(|w| w[0] == w[1] || keypad.contains_edge(w[0], w[1])) {
+        PinPattern::Walk
+    } else {
+        PinPattern::Random
+    }
+}
+
+/// Returns true if the digits count up or down by one, 0 following 9.
+fn is_sequence(keys: &[Key]) -> bool {
+    let digits = keys.iter().filter_map(|k| k.value.as_char().and_then(|c| c.to_digit(10))).collect::<Vec<_>>();
+    let steps = digits.windows(2).map(|w| (w[1] + 10 - w[0]) % 10).collect::<Vec<_>>();
+    digits.len() >= 3 && (steps.iter().all(|s| *s == 1) || steps.iter().all(|s| *s == 9))
+}
+
+/// Returns true if the second half of the points moves the same way as the 
+/// first half starting from a different key.
+fn is_repeated_shape(points: &[(i32, i32)]) -> bool {
+    let half = points.len() / 2;
+    if points.len() < 4 || points.len() % 2 != 0 {
+        return false;
+    }
+    let moves = |p: &[(i32, i32)]| {
+        p.windows(2)
+         .map(|w| (w[1].0 - w[0].0, w[1].1 - w[0].1))
+         .collect::<Vec<_>>()
+    };
+    points[0] != points[half] && moves(&points[..half]) == moves(&points[half..])
+}
+
+/// Estimates the number of PINs of a length in a pattern class. Classes 
+/// overlap slightly so these are upper bounds.
+fn class_size(keypad: &Keyboard,
+              positions: &HashMap<Key, (i32, i32)>,
+              pattern: PinPattern,
+              length: usize) -> f64 {
+
+    let digits = digit_positions(positions);
+    let count = digits.len() as f64;
+    let n = length as i32;
+    // Sequences using two or more keys from groups of keys
+    let grouped = |group: &dyn Fn((i32, i32)) -> i32| {
+        let mut sizes = HashMap::new();
+        for &(_, p) in digits.iter() {
+            *sizes.entry(group(p)).or_insert(0.0) += 1.0;
+        }
+        sizes.values()
+             .filter(|&&k| k > 1.0)
+             .map(|&k: &f64| k.powi(n) - k)
+             .sum::<f64>()
+    };
+
+    match pattern {
+        PinPattern::Repeated => count,
+        // Counting up or down from any digit
+        PinPattern::Sequence if length >= 3 => 2.0 * count,
+        PinPattern::Sequence => 0.0,
+        PinPattern::Row => grouped(&|p| p.1),
+        PinPattern::Column => grouped(&|p| p.0),
+        PinPattern::Diagonal => grouped(&|p| p.0 - p.1) + grouped(&|p| p.0 + p.1),
+        PinPattern::Corners => 4.0f64.powi(n) - 4.0,
+        PinPattern::Cross => 5.0f64.powi(n) - 5.0,
+        PinPattern::RepeatedShape if length >= 4 && length % 2 == 0 => {
+            count.powi(n / 2) * count
+        },
+        PinPattern::RepeatedShape => 0.0,
+        PinPattern::Walk => {
+            // Counts walks stepping between neighbouring digit keys
+            let keys = digits.iter()
+                             .filter_map(|&(c, _)| keypad.find_key(c))
+                             .collect::<Vec<_>>();
+            let mut walks = keys.iter().map(|k| (*k, 1.0)).collect::<HashMap<_, _>>();
+            for _ in 1..length {
+                walks = keys.iter()
+                            .map(|k| {
+                                let total = keys.iter()
+                                                .filter(|n| *n == k || keypad.contains_edge(**n, *k))
+                                                .map(|n| walks[n])
+                                                .sum::<f64>();
+                                (*k, total)
+                            })
+                            .collect();
+            }
+            walks.values().sum()
+        },
+        PinPattern::Random => count.powi(n),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use pin::*;
+
+    #[test]
+    fn test_patterns() {
+        let phone = generate_phone_keypad();
+        let pattern = |pin| analyse(&phone, pin).unwrap().pattern;
+        assert_eq!(pattern("0000"), PinPattern::Repeated);
+        assert_eq!(pattern("1234"), PinPattern::Sequence);
+        assert_eq!(pattern("7890"), PinPattern::Sequence);
+        assert_eq!(pattern("4321"), PinPattern::Sequence);
+        assert_eq!(pattern("1323"), PinPattern::Row);
+        assert_eq!(pattern("2580"), PinPattern::Column);
+        assert_eq!(pattern("159"), PinPattern::Diagonal);
+        assert_eq!(pattern("3579"), PinPattern::Random);
+        assert_eq!(pattern("1397"), PinPattern::Corners);
+        assert_eq!(pattern("2684"), PinPattern::Cross);
+        assert_eq!(pattern("1245"), PinPattern::RepeatedShape);
+        assert_eq!(pattern("1478"), PinPattern::Walk);
+        assert_eq!(pattern("9071"), PinPattern::Random);
+        assert!(analyse(&phone, "12a4").is_none());
+        assert!(analyse(&phone, "").is_none());
+
+        // The numpad is upside down compared to the phone
+        let numpad = generate_standard_numpad();
+        assert_eq!(analyse(&numpad, "7410").unwrap().pattern, PinPattern::Walk);
+        assert_eq!(pattern("7410"), PinPattern::Random);
+        assert_eq!(analyse(&numpad, "8520").unwrap().pattern, PinPattern::Column);
+        assert_eq!(analyse(&numpad, "1234").unwrap().pattern, PinPattern::Sequence);
+        assert_eq!(analyse(&generate_mac_numpad(), "147").unwrap().pattern, PinPattern::Column);
+    }
+
+    #[test]
+    fn test_guesses() {
+        let phone = generate_phone_keypad();
+        let guesses = |pin| analyse(&phone, pin).unwrap().guesses;
+        assert!(guesses("1111") < guesses("1234"));
+        assert!(guesses("1234") < guesses("2580"));
+        assert!(guesses("1234") <= 30.0);
+        assert!(guesses("1111") < guesses("2580"));
+        assert!(guesses("2580") < guesses("1478"));
+        assert!(guesses("1478") < guesses("9071"));
+        assert!(guesses("9071") <= 1.0e4);
+    }
+}
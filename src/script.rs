@@ -0,0 +1,133 @@
+//! Helpers for preparing text from scripts whose stored or displayed form
+//! differs from what was typed on the keyboard.
+//!
+//! Right-to-left text (hebrew, arabic) is stored in logical order, the order
+//! the keys were pressed, but text copied from a rendered display may contain
+//! directional control characters or arabic presentation forms produced by
+//! contextual shaping. Neither of these are on a keyboard so they're removed
+//! or folded back to the character the key produces.
+//!
+//! Japanese kana and korean hangul are also expanded into the characters 
+//! typed to produce them, see the `kana` and `hangul` modules.
+
+use hangul;
+use kana;
+
+/// Arabic presentation forms B. Each entry is the first code point of a run,
+/// the number of contextual forms in the run and the character they're shaped
+/// from.
+static PRESENTATION_FORMS: &[(u32, u32, char)] = &[
+    (0xFE70, 2, '\u{064B}'),
+    (0xFE72, 1, '\u{064C}'),
+    (0xFE74, 1, '\u{064D}'),
+    (0xFE76, 2, '\u{064E}'),
+    (0xFE78, 2, '\u{064F}'),
+    (0xFE7A, 2, '\u{0650}'),
+    (0xFE7C, 2, '\u{0651}'),
+    (0xFE7E, 2, '\u{0652}'),
+    (0xFE80, 1, 'ء'),
+    (0xFE81, 2, 'آ'),
+    (0xFE83, 2, 'أ'),
+    (0xFE85, 2, 'ؤ'),
+    (0xFE87, 2, 'إ'),
+    (0xFE89, 4, 'ئ'),
+    (0xFE8D, 2, 'ا'),
+    (0xFE8F, 4, 'ب'),
+    (0xFE93, 2, 'ة'),
+    (0xFE95, 4, 'ت'),
+    (0xFE99, 4, 'ث'),
+    (0xFE9D, 4, 'ج'),
+    (0xFEA1, 4, 'ح'),
+    (0xFEA5, 4, 'خ'),
+    (0xFEA9, 2, 'د'),
+    (0xFEAB, 2, 'ذ'),
+    (0xFEAD, 2, 'ر'),
+    (0xFEAF, 2, 'ز'),
+    (0xFEB1, 4, 'س'),
+    (0xFEB5, 4, 'ش'),
+    (0xFEB9, 4, 'ص'),
+    (0xFEBD, 4, 'ض'),
+    (0xFEC1, 4, 'ط'),
+    (0xFEC5, 4, 'ظ'),
+    (0xFEC9, 4, 'ع'),
+    (0xFECD, 4, 'غ'),
+    (0xFED1, 4, 'ف'),
+    (0xFED5, 4, 'ق'),
+    (0xFED9, 4, 'ك'),
+    (0xFEDD, 4, 'ل'),
+    (0xFEE1, 4, 'م'),
+    (0xFEE5, 4, 'ن'),
+    (0xFEE9, 4, 'ه'),
+    (0xFEED, 2, 'و'),
+    (0xFEEF, 2, 'ى'),
+    (0xFEF1, 4, 'ي'),
+    // Lam-alef ligatures are typed with a single key so they fold to the
+    // isolated ligature rather than two letters.
+    (0xFEF5, 2, '\u{FEF5}'),
+    (0xFEF7, 2, '\u{FEF7}'),
+    (0xFEF9, 2, '\u{FEF9}'),
+    (0xFEFB, 2, '\u{FEFB}'),
+];
+
+/// Returns true if the character is an invisible control affecting display
+/// direction (marks, embeddings, overrides and isolates).
+pub fn is_direction_control(c: char) -> bool {
+    matches!(c, '\u{061C}' | '\u{200E}' | '\u{200F}' |
+                '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// Returns true if the character belongs to a script written right-to-left.
+pub fn is_right_to_left(c: char) -> bool {
+    matches!(c, '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' |
+                '\u{FE70}'..='\u{FEFF}')
+}
+
+/// Folds an arabic presentation form back to the character typed to produce
+/// it. Other characters are returned unchanged.
+pub fn unshape(c: char) -> char {
+    let code = c as u32;
+    PRESENTATION_FORMS.iter()
+                      .find(|&&(start, count, _)| code >= start && code < start + count)
+                      .map_or(c, |&(_, _, base)| base)
+}
+
+/// Normalises text into the characters typed on the keyboard, in the order
+/// they were typed. Direction controls are removed, presentation forms are
+/// unshaped and voiced kana and hangul syllables are split into their
+/// keystrokes. Adjacency in the keyboard graphs is symmetric so once normalised
+/// walks are found regardless of whether the text was displayed right-to-left
+/// or left-to-right.
+pub fn normalise(text: &str) -> String {
+    text.chars()
+        .filter(|c| !is_direction_control(*c))
+        .map(unshape)
+        .flat_map(kana::keystrokes)
+        .flat_map(hangul::keystrokes)
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use script::*;
+
+    #[test]
+    fn test_unshape() {
+        // Isolated, final, initial and medial beh
+        for c in "\u{FE8F}\u{FE90}\u{FE91}\u{FE92}".chars() {
+            assert_eq!(unshape(c), 'ب');
+        }
+        assert_eq!(unshape('\u{FEFC}'), '\u{FEFB}');
+        assert_eq!(unshape('\u{FE7D}'), '\u{0651}');
+        assert_eq!(unshape('a'), 'a');
+        assert_eq!(unshape('ب'), 'ب');
+    }
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!(normalise("\u{200F}\u{FEB3}\u{FEE0}\u{FE8E}\u{FEE1}"), "سلام");
+        assert_eq!(normalise("\u{202B}שלום\u{202C}"), "שלום");
+        assert!("שלום".chars().all(is_right_to_left));
+        assert!(!is_right_to_left('a'));
+    }
+}
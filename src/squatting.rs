@@ -0,0 +1,163 @@
+//! Typosquatting candidates for domain and package names.
+//!
+//! Lookalike names are registered to catch users mistyping a popular name, 
+//! these are mostly a single keyboard typo away from it. Candidates are
+//! generated on each layout given and scored by the keyboard weighted 
+//! distance from the name, so the most likely typos come first.
+
+use distance::KeyboardDistance;
+use typo::TypoModel;
+use ::*;
+
+/// Typo producing a candidate name
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SquatKind {
+    /// A character replaced by one on a neighbouring key
+    Replacement,
+    /// A neighbouring key typed before or after a character
+    Insertion,
+    /// A character left out
+    Omission,
+    /// A character typed twice
+    Doubling,
+    /// Two characters swapped
+    Transposition,
+}
+
+/// A candidate typosquatting name
+#[derive(Debug, PartialEq, Clone)]
+pub struct SquatCandidate {
+    /// The candidate name
+    pub name: String,
+    /// Typo producing the name
+    pub kind: SquatKind,
+    /// Smallest keyboard distance from the original name on any layout
+    pub score: f64,
+    /// Indices of the layouts the typo was made on
+    pub layouts: Vec<usize>,
+}
+
+/// Returns true for characters allowed in a domain name label
+pub fn is_domain_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-'
+}
+
+/// Returns true for characters allowed in a crate name
+pub fn is_crate_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Generates typosquatting candidates for a name on each of the layouts. 
+/// Candidates with a character `valid` rejects are skipped. Candidates are 
+/// sorted by score and then name, a candidate found on several layouts is only
+/// returned once.
+pub fn candidates<F>(name: &str, layouts: &[&Keyboard], valid: F) -> Vec<SquatCandidate>
+    where F: Fn(char) -> bool
+{
+    let chars = name.chars().map(|c| c.to_string()).collect::<Vec<_>>();
+    let mut result: Vec<SquatCandidate> = Vec::new();
+
+    for (index, layout) in layouts.iter().enumerate() {
+        let model = TypoModel::new(layout);
+        let distance = KeyboardDistance::new(layout);
+        let mut typos = Vec::new();
+        for (i, c) in name.chars().enumerate() {
+            for n in model.neighbours(c) {
+                let mut typo = chars.clone();
+                typo[i] = n.clone();
+                typos.push((typo, SquatKind::Replacement));
+                for position in [i, i + 1].iter() {
+                    let mut typo = chars.clone();
+                    typo.insert(*position, n.clone());
+                    // Typing the key next to it twice is a doubling
+                    let doubled = (*position > 0 && typo[*position - 1] == n) ||
+                                  typo.get(*position + 1) == Some(&n);
+                    if !doubled {
+                        typos.push((typo, SquatKind::Insertion));
+                    }
+                }
+            }
+            let mut typo = chars.clone();
+            typo.remove(i);
+            typos.push((typo, SquatKind::Omission));
+
+            let mut typo = chars.clone();
+            typo.insert(i, c.to_string());
+            typos.push((typo, SquatKind::Doubling));
+
+            if i + 1 < chars.len() && chars[i] != chars[i + 1] {
+                let mut typo = chars.clone();
+                typo.swap(i, i + 1);
+                typos.push((typo, SquatKind::Transposition));
+            }
+        }
+
+        for (typo, kind) in typos {
+            let typo = typo.concat();
+            if typo.is_empty() || !typo.chars().all(&valid) {
+                continue;
+            }
+            if typo == name {
+                continue;
+            }
+            let score = distance.damerau(name, &typo);
+            match result.iter_mut().find(|c| c.name == typo) {
+                Some(existing) => {
+                    existing.score = existing.score.min(score);
+                    if !existing.layouts.contains(&index) {
+                        existing.layouts.push(index);
+                    }
+                },
+                None => result.push(SquatCandidate {
+                    name: typo,
+                    kind,
+                    score,
+                    layouts: vec![index],
+                }),
+            }
+        }
+    }
+    result.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap().then_with(|| a.name.cmp(&b.name)));
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use squatting::*;
+
+    #[test]
+    fn test_candidates() {
+        let qwerty = generate_qwerty_us();
+        let found = candidates("serde", &[&qwerty], is_crate_char);
+        let find = |name: &str| found.iter().find(|c| c.name == name).cloned();
+
+        assert_eq!(find("sedre").unwrap().kind, SquatKind::Transposition);
+        assert_eq!(find("serd").unwrap().kind, SquatKind::Omission);
+        assert_eq!(find("serrde").unwrap().kind, SquatKind::Doubling);
+        assert_eq!(find("derde").unwrap().kind, SquatKind::Replacement);
+        assert_eq!(find("serdwe").unwrap().kind, SquatKind::Insertion);
+        assert!(find("serde").is_none());
+        assert!(find("pserde").is_none());
+        assert!(found.iter().all(|c| c.name.chars().all(is_crate_char)));
+        assert!(found.windows(2).all(|w| w[0].score <= w[1].score));
+        assert!(find("derde").unwrap().score < find("serd").unwrap().score);
+    }
+
+    #[test]
+    fn test_layouts() {
+        let qwerty = generate_qwerty_us();
+        let dvorak = generate_dvorak();
+        let found = candidates("google", &[&qwerty, &dvorak], is_domain_char);
+
+        let mut names = found.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), found.len());
+
+        let shared = found.iter().find(|c| c.name == "gogle").unwrap();
+        assert_eq!(shared.layouts, vec![0, 1]);
+        // p is next to o on qwerty but not dvorak
+        assert_eq!(found.iter().find(|c| c.name == "gpogle").unwrap().layouts, vec![0]);
+    }
+}
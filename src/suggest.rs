@@ -0,0 +1,187 @@
+//! "Did you mean" suggestions ranked by keyboard weighted distance.
+//!
+//! Words are kept in a BK-tree, each child is stored under its distance from
+//! the parent so the triangle inequality rules out most of the tree for a 
+//! query. The keyboard weighted Levenshtein distance is a metric as long as 
+//! insertion and deletion cost the same, distances are bucketed to a fixed 
+//! precision as they aren't whole numbers.
+
+use distance::KeyboardDistance;
+use ::*;
+
+/// Buckets per unit of distance used to key the children of a node
+const BUCKET_SCALE: f64 = 1000.0;
+
+/// A suggested word
+#[derive(Debug, PartialEq, Clone)]
+pub struct Suggestion {
+    /// The word from the index
+    pub word: String,
+    /// Keyboard weighted distance from the query
+    pub distance: f64,
+}
+
+struct Node {
+    word: String,
+    children: Vec<(i64, usize)>,
+}
+
+/// Index of words answering nearest neighbour queries by keyboard weighted 
+/// distance
+pub struct SuggestionIndex<'a> {
+    distance: KeyboardDistance<'a>,
+    nodes: Vec<Node>,
+}
+
+fn bucket(distance: f64) -> i64 {
+    (distance * BUCKET_SCALE).round() as i64
+}
+
+impl<'a> SuggestionIndex<'a> {
+    /// Creates an empty index using the default edit costs on a keyboard
+    pub fn new(keyboard: &'a Keyboard) -> SuggestionIndex<'a> {
+        SuggestionIndex::with_distance(KeyboardDistance::new(keyboard))
+    }
+
+    /// Creates an empty index using a keyboard distance. Insertion and 
+    /// deletion should cost the same otherwise suggestions may be missed.
+    pub fn with_distance(distance: KeyboardDistance<'a>) -> SuggestionIndex<'a> {
+        SuggestionIndex {
+            distance,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Creates an index of words using the default edit costs on a keyboard
+    pub fn from_words(keyboard: &'a Keyboard, words: &[&str]) -> SuggestionIndex<'a> {
+        let mut index = SuggestionIndex::new(keyboard);
+        for word in words {
+            index.insert(word);
+        }
+        index
+    }
+
+    /// Number of words in the index
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true if the index has no words
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Adds a word to the index, returns false if it was already there
+    pub fn insert(&mut self, word: &str) -> bool {
+        let new = self.nodes.len();
+        if self.nodes.is_empty() {
+            self.nodes.push(Node { word: word.to_string(), children: Vec::new() });
+            return true;
+        }
+        let mut current = 0;
+        loop {
+            if self.nodes[current].word == word {
+                return false;
+            }
+            let key = bucket(self.distance.levenshtein(&self.nodes[current].word, word));
+            let child = self.nodes[current].children
+                                           .iter()
+                                           .find(|&&(k, _)| k == key)
+                                           .map(|&(_, c)| c);
+            match child {
+                Some(c) => current = c,
+                None => {
+                    self.nodes[current].children.push((key, new));
+                    self.nodes.push(Node { word: word.to_string(), children: Vec::new() });
+                    return true;
+                },
+            }
+        }
+    }
+
+    /// Returns the words within a distance of the query, closest first and
+    /// then in alphabetical order
+    pub fn suggest(&self, query: &str, threshold: f64) -> Vec<Suggestion> {
+        let mut result = Vec::new();
+        let mut pending = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(current) = pending.pop() {
+            let node = &self.nodes[current];
+            let distance = self.distance.levenshtein(query, &node.word);
+            if distance <= threshold {
+                result.push(Suggestion { word: node.word.clone(), distance });
+            }
+            // Widened by a bucket either side for rounding
+            let low = ((distance - threshold) * BUCKET_SCALE).floor() as i64 - 1;
+            let high = ((distance + threshold) * BUCKET_SCALE).ceil() as i64 + 1;
+            pending.extend(node.children
+                               .iter()
+                               .filter(|&&(k, _)| k >= low && k <= high)
+                               .map(|&(_, c)| c));
+        }
+        result.sort_by(|a, b| {
+            a.distance.partial_cmp(&b.distance).unwrap().then_with(|| a.word.cmp(&b.word))
+        });
+        result
+    }
+
+    /// Returns up to `count` of the closest words within a distance of the query
+    pub fn closest(&self, query: &str, threshold: f64, count: usize) -> Vec<Suggestion> {
+        let mut result = self.suggest(query, threshold);
+        result.truncate(count);
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use suggest::*;
+
+    const COMMANDS: [&str; 10] = ["build", "check", "clean", "clippy", "doc", 
+                                  "fetch", "fmt", "run", "test", "update"];
+
+    #[test]
+    fn test_suggest() {
+        let qwerty = generate_qwerty_us();
+        let index = SuggestionIndex::from_words(&qwerty, &COMMANDS);
+        assert_eq!(index.len(), COMMANDS.len());
+
+        // v is next to b, so "vuild" is closer to build than "xuild"
+        let vuild = index.suggest("vuild", 1.0);
+        assert_eq!(vuild[0].word, "build");
+        assert_eq!(vuild[0].distance, 0.5);
+        assert!(index.suggest("xuild", 0.5).is_empty());
+        assert_eq!(index.suggest("xuild", 1.0)[0].word, "build");
+
+        assert_eq!(index.suggest("test", 0.0)[0].distance, 0.0);
+        assert_eq!(index.closest("chexk", 1.0, 1)[0].word, "check");
+        assert!(index.closest("zzzzzz", 1.0, 3).is_empty());
+    }
+
+    #[test]
+    fn test_matches_linear_search() {
+        let qwerty = generate_qwerty_us();
+        let distance = KeyboardDistance::new(&qwerty);
+        let mut index = SuggestionIndex::new(&qwerty);
+        for word in COMMANDS.iter() {
+            assert!(index.insert(word));
+        }
+        assert!(!index.insert("fmt"));
+
+        for query in ["cleen", "tets", "fethc", "rn", "clipy"].iter() {
+            for threshold in [0.5, 1.0, 2.0, 3.5].iter() {
+                let mut expected = COMMANDS.iter()
+                                           .filter(|w| distance.levenshtein(query, w) <= *threshold)
+                                           .map(|w| w.to_string())
+                                           .collect::<Vec<_>>();
+                let mut found = index.suggest(query, *threshold)
+                                     .into_iter()
+                                     .map(|s| s.word)
+                                     .collect::<Vec<_>>();
+                expected.sort();
+                found.sort();
+                assert_eq!(found, expected);
+            }
+        }
+    }
+}
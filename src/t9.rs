@@ -0,0 +1,90 @@
+//! Letters on phone keypads (ITU E.161), see `generate_phone_keypad`.
+//!
+//! Letters are typed either with predictive T9 input, pressing each digit
+//! once per letter, or by multi-tap where a key is pressed repeatedly to cycle
+//! through its letters (2 = a, 22 = b, 222 = c).
+
+/// Letters printed on each key of the phone keypad
+static KEYPAD_LETTERS: &[(char, &str)] = &[
+    ('2', "abc"),
+    ('3', "def"),
+    ('4', "ghi"),
+    ('5', "jkl"),
+    ('6', "mno"),
+    ('7', "pqrs"),
+    ('8', "tuv"),
+    ('9', "wxyz"),
+];
+
+/// Returns the letters printed on a key, the string is empty for keys without
+/// letters.
+pub fn letters(digit: char) -> &'static str {
+    KEYPAD_LETTERS.iter()
+                  .find(|&&(d, _)| d == digit)
+                  .map_or("", |&(_, l)| l)
+}
+
+/// Returns the key a letter is on and the number of presses to type it with
+/// multi-tap. Letters are matched regardless of case.
+pub fn find_letter(letter: char) -> Option<(char, usize)> {
+    let letter = letter.to_ascii_lowercase();
+    KEYPAD_LETTERS.iter()
+                  .filter_map(|&(d, l)| l.chars().position(|c| c == letter).map(|i| (d, i + 1)))
+                  .next()
+}
+
+/// Encodes text as the digits typed with T9 predictive input. Digits are
+/// typed as themselves and spaces with the 0 key. Returns None if any other
+/// character can't be typed.
+pub fn encode(text: &str) -> Option<String> {
+    text.chars()
+        .map(|c| match c {
+            '0'..='9' => Some(c),
+            ' ' => Some('0'),
+            _ => find_letter(c).map(|(d, _)| d),
+        })
+        .collect()
+}
+
+/// Encodes text as the keys pressed with multi-tap input. Consecutive letters
+/// on the same key are separated by a pause which isn't a key press, so the
+/// result is ambiguous where that happens. Returns None if a character isn't
+/// a letter or space.
+pub fn multi_tap(text: &str) -> Option<String> {
+    let mut result = String::new();
+    for c in text.chars() {
+        if c == ' ' {
+            result.push('0');
+        } else {
+            let (digit, presses) = find_letter(c)?;
+            result.extend(std::iter::repeat(digit).take(presses));
+        }
+    }
+    Some(result)
+}
+
+/// Returns true if the digits are the T9 encoding of the word, i.e. a PIN
+/// spelling out a word.
+pub fn matches(digits: &str, word: &str) -> bool {
+    encode(word).is_some_and(|encoded| encoded == digits)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use t9::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(letters('7'), "pqrs");
+        assert_eq!(letters('1'), "");
+        assert_eq!(find_letter('S'), Some(('7', 4)));
+        assert_eq!(encode("hello"), Some("43556".to_string()));
+        assert_eq!(encode("Call me 2"), Some("225506302".to_string()));
+        assert_eq!(encode("hi!"), None);
+        assert_eq!(multi_tap("hello"), Some("4433555555666".to_string()));
+        assert_eq!(multi_tap("hi 5"), None);
+        assert!(matches("7277", "pass"));
+        assert!(!matches("7278", "pass"));
+    }
+}
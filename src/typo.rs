@@ -0,0 +1,521 @@
+//! Probabilistic model of typing mistakes on a keyboard (a noisy channel).
+//!
+//! Each intended character is typed correctly or one of these mistakes is
+//! made:
+//!
+//! * Substitution - a neighbouring key is pressed instead
+//! * Shift slip - the other level of the same key is typed
+//! * Omission - the key isn't pressed
+//! * Insertion - a neighbouring key is pressed as well
+//! * Transposition - the key is pressed after the next one
+//!
+//! The probability of a mistake is split evenly between the keys it could 
+//! type, so the probability of typing some text given the intended text is 
+//! found by summing over every way the mistakes could have produced it.
+//!
+//! The model also generates typos of some text, either every distinct typo
+//! within a number of mistakes or sampled by their probability. Typing the
+//! whole text with caps lock on is included as a mistake when generating.
+
+use std::collections::BTreeSet;
+use ::*;
+
+/// Probability of each mistake when typing a character
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TypoPriors {
+    /// A neighbouring key is pressed instead
+    pub substitution: f64,
+    /// Shift is pressed or released by mistake
+    pub shift_slip: f64,
+    /// The key is missed
+    pub omission: f64,
+    /// A neighbouring key is pressed as well
+    pub insertion: f64,
+    /// The key is pressed after the following key
+    pub transposition: f64,
+    /// The whole text is typed with caps lock on, this is per text rather
+    /// than per character
+    pub caps_lock: f64,
+}
+
+impl Default for TypoPriors {
+    fn default() -> TypoPriors {
+        TypoPriors {
+            substitution: 0.01,
+            shift_slip: 0.005,
+            omission: 0.005,
+            insertion: 0.005,
+            transposition: 0.003,
+            caps_lock: 0.001,
+        }
+    }
+}
+
+impl TypoPriors {
+    /// Probability of a character being typed without a mistake
+    pub fn correct(&self) -> f64 {
+        1.0 - self.substitution - self.shift_slip - self.omission - self.insertion - self.transposition
+    }
+}
+
+/// Characters typed under the priors when fitting to a corpus, this is how
+/// strongly the priors are held against the corpus.
+const PRIOR_WEIGHT: f64 = 100.0;
+
+/// Mistakes found when aligning intended and typed text
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Mistake {
+    Correct,
+    Substitution,
+    ShiftSlip,
+    Omission,
+    Insertion,
+    Transposition,
+    /// A mistake the model doesn't describe, i.e. a key across the keyboard
+    Other,
+}
+
+/// Kinds of mistake made in a generated typo
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum TypoKind {
+    Substitution,
+    ShiftSlip,
+    Omission,
+    Insertion,
+    Transposition,
+    CapsLock,
+}
+
+/// A typo of some text with a single mistake
+#[derive(Debug, PartialEq, Clone)]
+pub struct Typo {
+    /// The text typed
+    pub text: String,
+    /// The mistake made
+    pub kind: TypoKind,
+    /// Probability of making the mistake
+    pub probability: f64,
+}
+
+/// Noisy channel typo model for a keyboard
+pub struct TypoModel<'a> {
+    keyboard: &'a Keyboard,
+    priors: TypoPriors,
+}
+
+impl<'a> TypoModel<'a> {
+    /// Creates a model for a keyboard with the default priors
+    pub fn new(keyboard: &'a Keyboard) -> TypoModel<'a> {
+        TypoModel::with_priors(keyboard, TypoPriors::default())
+    }
+
+    /// Creates a model for a keyboard with the given priors
+    pub fn with_priors(keyboard: &'a Keyboard, priors: TypoPriors) -> TypoModel<'a> {
+        TypoModel {
+            keyboard,
+            priors,
+        }
+    }
+
+    /// Returns the priors of the model
+    pub fn priors(&self) -> &TypoPriors {
+        &self.priors
+    }
+
+    /// Returns the text typed by the keys neighbouring the key typing `c` at 
+    /// the same level as `c`, a single character unless the key types a 
+    /// cluster (see `cluster`). Keys with nothing at that level are skipped.
+    pub fn neighbours(&self, c: char) -> Vec<String> {
+        let key = match self.keyboard.find_key(c) {
+            Some(k) => k,
+            None => return Vec::new(),
+        };
+        let shifted = key.value != c;
+        let mut result = self.keyboard
+            .neighbors_directed(key, petgraph::Direction::Outgoing)
+            .filter(|n| self.keyboard.edge_weight(key, *n).is_some_and(|e| !e.is_same_key()))
+            .map(|n| if shifted { n.shifted_output() } else { n.value_output() })
+            .filter(|n| !n.is_empty())
+            .collect::<Vec<_>>();
+        result.sort();
+        result.dedup();
+        result
+    }
+
+    /// Returns the text typed by the same key as `c` at the other shift
+    /// level, if there is any.
+    pub fn shift_slip(&self, c: char) -> Option<String> {
+        self.keyboard.find_key(c).and_then(|k| {
+            let other = if k.value == c { k.shifted_output() } else { k.value_output() };
+            if other.is_empty() { None } else { Some(other) }
+        })
+    }
+
+    /// Probability of typing `typed` when intending to type `intended`
+    pub fn char_probability(&self, intended: char, typed: char) -> f64 {
+        if intended == typed {
+            return self.priors.correct();
+        }
+        if self.shift_slip(intended) == Some(typed.to_string()) {
+            return self.priors.shift_slip;
+        }
+        let neighbours = self.neighbours(intended);
+        if neighbours.contains(&typed.to_string()) {
+            self.priors.substitution / neighbours.len() as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Probability of also typing `inserted` when typing `intended`
+    pub fn insertion_probability(&self, intended: char, inserted: char) -> f64 {
+        let neighbours = self.neighbours(intended);
+        if neighbours.contains(&inserted.to_string()) {
+            self.priors.insertion / neighbours.len() as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Probability of typing the text `typed` when intending to type 
+    /// `intended`. This sums every combination of mistakes producing the text
+    /// so is small for long text even if typed correctly, compare it between
+    /// candidates for the same typed text.
+    pub fn probability(&self, intended: &str, typed: &str) -> f64 {
+        let a = intended.chars().collect::<Vec<_>>();
+        let b = typed.chars().collect::<Vec<_>>();
+        let mut table = vec![vec![0.0; b.len() + 1]; a.len() + 1];
+        table[0][0] = 1.0;
+        for i in 0..=a.len() {
+            for j in 0..=b.len() {
+                let p = table[i][j];
+                if p == 0.0 {
+                    continue;
+                }
+                if i < a.len() {
+                    table[i + 1][j] += p * self.priors.omission;
+                }
+                if i < a.len() && j < b.len() {
+                    table[i + 1][j + 1] += p * self.char_probability(a[i], b[j]);
+                }
+                // Inserted keys are next to the key just typed
+                if i > 0 && j < b.len() {
+                    table[i][j + 1] += p * self.insertion_probability(a[i - 1], b[j]);
+                }
+                if i + 1 < a.len() && j + 1 < b.len() &&
+                   a[i] != a[i + 1] && a[i] == b[j + 1] && a[i + 1] == b[j] {
+                    table[i + 2][j + 2] += p * self.priors.transposition;
+                }
+            }
+        }
+        table[a.len()][b.len()]
+    }
+
+    /// Returns every typo of the text with a single mistake. The same text 
+    /// may be produced by different mistakes.
+    pub fn single_typos(&self, text: &str) -> Vec<Typo> {
+        let chars = text.chars().collect::<Vec<_>>();
+        let typed = chars.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let mut result = Vec::new();
+        let mut push = |typed: Vec<String>, kind, probability| {
+            result.push(Typo {
+                text: typed.concat(),
+                kind,
+                probability,
+            });
+        };
+        for (i, c) in chars.iter().enumerate() {
+            let neighbours = self.neighbours(*c);
+            for n in neighbours.iter() {
+                let mut typo = typed.clone();
+                typo[i] = n.clone();
+                push(typo, TypoKind::Substitution,
+                     self.priors.substitution / neighbours.len() as f64);
+
+                let mut typo = typed.clone();
+                typo.insert(i + 1, n.clone());
+                push(typo, TypoKind::Insertion,
+                     self.priors.insertion / neighbours.len() as f64);
+            }
+            if let Some(other) = self.shift_slip(*c) {
+                let mut typo = typed.clone();
+                typo[i] = other;
+                push(typo, TypoKind::ShiftSlip, self.priors.shift_slip);
+            }
+            let mut typo = typed.clone();
+            typo.remove(i);
+            push(typo, TypoKind::Omission, self.priors.omission);
+
+            if i + 1 < chars.len() && chars[i] != chars[i + 1] {
+                let mut typo = typed.clone();
+                typo.swap(i, i + 1);
+                push(typo, TypoKind::Transposition, self.priors.transposition);
+            }
+        }
+        let caps_lock = self.caps_lock(&chars);
+        if caps_lock != typed {
+            push(caps_lock, TypoKind::CapsLock, self.priors.caps_lock);
+        }
+        result
+    }
+
+    /// Inverts the shift level of every letter as caps lock does.
+    fn caps_lock(&self, chars: &[char]) -> Vec<String> {
+        chars.iter()
+             .map(|c| if c.is_alphabetic() {
+                 self.shift_slip(*c).unwrap_or_else(|| c.to_string())
+             } else {
+                 c.to_string()
+             })
+             .collect()
+    }
+
+    /// Returns every distinct typo of the text with up to `edits` mistakes in
+    /// sorted order. The text itself isn't included.
+    pub fn typos(&self, text: &str, edits: usize) -> Vec<String> {
+        let mut found = BTreeSet::new();
+        let mut current = vec![text.to_string()];
+        for _ in 0..edits {
+            let mut next = Vec::new();
+            for t in current.iter() {
+                for typo in self.single_typos(t) {
+                    if typo.text != text && found.insert(typo.text.clone()) {
+                        next.push(typo.text);
+                    }
+                }
+            }
+            current = next;
+        }
+        found.into_iter().collect()
+    }
+
+    /// Samples a typo of the text with `edits` mistakes, each chosen with 
+    /// probability proportional to how likely it is. `uniform` is called for 
+    /// random numbers in [0, 1). Returns None if the text has no typos.
+    pub fn sample_typo<F>(&self, text: &str, edits: usize, uniform: &mut F) -> Option<String>
+        where F: FnMut() -> f64
+    {
+        let mut result = text.to_string();
+        for _ in 0..edits {
+            let typos = self.single_typos(&result);
+            let total = typos.iter().map(|t| t.probability).sum::<f64>();
+            if total <= 0.0 {
+                return None;
+            }
+            let mut target = uniform() * total;
+            let mut chosen = None;
+            for typo in typos.into_iter() {
+                target -= typo.probability;
+                chosen = Some(typo);
+                if target < 0.0 {
+                    break;
+                }
+            }
+            result = chosen?.text;
+        }
+        Some(result)
+    }
+
+    /// Fits the priors to a corpus of intended and typed text, returning a new
+    /// model. The mistakes in each pair are found by aligning them with the
+    /// fewest edits, the current priors are weighted as if seen over 100
+    /// characters.
+    pub fn fit(&self, corpus: &[(&str, &str)]) -> TypoModel<'a> {
+        let mut counts = [0.0; 5];
+        let mut total = 0.0;
+        for &(intended, typed) in corpus.iter() {
+            for mistake in self.align(intended, typed) {
+                let index = match mistake {
+                    Mistake::Correct | Mistake::Other => None,
+                    Mistake::Substitution => Some(0),
+                    Mistake::ShiftSlip => Some(1),
+                    Mistake::Omission => Some(2),
+                    Mistake::Insertion => Some(3),
+                    Mistake::Transposition => Some(4),
+                };
+                if let Some(i) = index {
+                    counts[i] += 1.0;
+                }
+                // Insertions are made as well as typing a character
+                if mistake != Mistake::Insertion {
+                    total += 1.0;
+                }
+            }
+        }
+        let rate = |count: f64, prior: f64| {
+            (count + prior * PRIOR_WEIGHT) / (total + PRIOR_WEIGHT)
+        };
+        TypoModel::with_priors(self.keyboard, TypoPriors {
+            substitution: rate(counts[0], self.priors.substitution),
+            shift_slip: rate(counts[1], self.priors.shift_slip),
+            omission: rate(counts[2], self.priors.omission),
+            insertion: rate(counts[3], self.priors.insertion),
+            transposition: rate(counts[4], self.priors.transposition),
+            caps_lock: self.priors.caps_lock,
+        })
+    }
+
+    /// Aligns intended and typed text with the fewest edits and returns the
+    /// mistake made for each edit.
+    fn align(&self, intended: &str, typed: &str) -> Vec<Mistake> {
+        let a = intended.chars().collect::<Vec<_>>();
+        let b = typed.chars().collect::<Vec<_>>();
+        let mut table = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+        for (i, row) in table.iter_mut().enumerate() {
+            row[0] = i;
+        }
+        for (j, cell) in table[0].iter_mut().enumerate() {
+            *cell = j;
+        }
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                let substitution = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+                let mut best = (table[i - 1][j] + 1)
+                    .min(table[i][j - 1] + 1)
+                    .min(table[i - 1][j - 1] + substitution);
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    best = best.min(table[i - 2][j - 2] + 1);
+                }
+                table[i][j] = best;
+            }
+        }
+
+        let mut result = Vec::new();
+        let (mut i, mut j) = (a.len(), b.len());
+        while i > 0 || j > 0 {
+            if i > 0 && j > 0 && a[i - 1] == b[j - 1] && table[i][j] == table[i - 1][j - 1] {
+                result.push(Mistake::Correct);
+                i -= 1;
+                j -= 1;
+            } else if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] &&
+                      table[i][j] == table[i - 2][j - 2] + 1 {
+                result.push(Mistake::Transposition);
+                i -= 2;
+                j -= 2;
+            } else if i > 0 && j > 0 && table[i][j] == table[i - 1][j - 1] + 1 {
+                result.push(if self.shift_slip(a[i - 1]) == Some(b[j - 1].to_string()) {
+                    Mistake::ShiftSlip
+                } else if self.neighbours(a[i - 1]).contains(&b[j - 1].to_string()) {
+                    Mistake::Substitution
+                } else {
+                    Mistake::Other
+                });
+                i -= 1;
+                j -= 1;
+            } else if i > 0 && table[i][j] == table[i - 1][j] + 1 {
+                result.push(Mistake::Omission);
+                i -= 1;
+            } else {
+                result.push(Mistake::Insertion);
+                j -= 1;
+            }
+        }
+        result.reverse();
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use typo::*;
+
+    #[test]
+    fn test_char_probability() {
+        let qwerty = generate_qwerty_us();
+        let model = TypoModel::new(&qwerty);
+        assert_eq!(model.neighbours('s'), vec!["a", "d", "e", "w", "x", "z"]);
+        assert_eq!(model.neighbours('S'), vec!["A", "D", "E", "W", "X", "Z"]);
+        assert_eq!(model.shift_slip('1'), Some("!".to_string()));
+        let inscript = generate_inscript();
+        let model = TypoModel::new(&inscript);
+        assert!(model.neighbours('(').contains(&"श्र".to_string()));
+        assert_eq!(model.shift_slip('7'), Some("क्ष".to_string()));
+        let model = TypoModel::new(&qwerty);
+        assert!((model.char_probability('s', 's') - 0.972).abs() < 1e-9);
+        assert!((model.char_probability('s', 'd') - 0.01 / 6.0).abs() < 1e-9);
+        assert_eq!(model.char_probability('s', 'S'), 0.005);
+        assert_eq!(model.char_probability('s', 'p'), 0.0);
+        assert_eq!(model.insertion_probability('s', 'p'), 0.0);
+    }
+
+    #[test]
+    fn test_probability() {
+        let qwerty = generate_qwerty_us();
+        let model = TypoModel::new(&qwerty);
+        let correct = model.probability("hello", "hello");
+        let neighbour = model.probability("hello", "hwllo");
+        assert!(correct > neighbour);
+        assert!(neighbour > 0.0);
+        assert_eq!(model.probability("hello", "hpllo"), 0.0);
+        assert!(model.probability("hello", "hlelo") > 0.0);
+        assert!(model.probability("hello", "helo") > 0.0);
+        assert!(model.probability("hello", "hjello") > 0.0);
+        // The typed text is more likely from "hello" than from "help"
+        assert!(model.probability("hello", "hrllo") > model.probability("help", "hrllo"));
+    }
+
+    #[test]
+    fn test_typos() {
+        let qwerty = generate_qwerty_us();
+        let model = TypoModel::new(&qwerty);
+        let single = model.single_typos("ab");
+        let kinds = |kind| single.iter().filter(|t| t.kind == kind).count();
+        // a has 4 neighbours and b has 4
+        assert_eq!(kinds(TypoKind::Substitution), 8);
+        assert_eq!(kinds(TypoKind::Insertion), 8);
+        assert_eq!(kinds(TypoKind::ShiftSlip), 2);
+        assert_eq!(kinds(TypoKind::Omission), 2);
+        assert_eq!(kinds(TypoKind::Transposition), 1);
+        assert!(single.contains(&Typo {
+            text: "AB".to_string(),
+            kind: TypoKind::CapsLock,
+            probability: 0.001,
+        }));
+
+        let typos = model.typos("ab", 1);
+        assert!(typos.contains(&"sb".to_string()));
+        assert!(typos.contains(&"ba".to_string()));
+        assert!(typos.contains(&"a".to_string()));
+        assert!(!typos.contains(&"ab".to_string()));
+        assert!(!typos.contains(&"pb".to_string()));
+        let mut sorted = typos.clone();
+        sorted.dedup();
+        assert_eq!(sorted, typos);
+        assert!(model.typos("ab", 2).len() > typos.len());
+        assert!(model.typos("ab", 2).contains(&"s".to_string()));
+    }
+
+    #[test]
+    fn test_sample_typo() {
+        let qwerty = generate_qwerty_us();
+        let model = TypoModel::new(&qwerty);
+        let mut state = 0.0;
+        let mut uniform = || {
+            state = (state + 0.37) % 1.0;
+            state
+        };
+        for _ in 0..20 {
+            let typo = model.sample_typo("secret", 1, &mut uniform).unwrap();
+            assert!(model.typos("secret", 1).contains(&typo));
+        }
+        assert_eq!(model.sample_typo("", 1, &mut uniform), None);
+        assert_eq!(model.sample_typo("secret", 0, &mut uniform), Some("secret".to_string()));
+    }
+
+    #[test]
+    fn test_fit() {
+        let qwerty = generate_qwerty_us();
+        let model = TypoModel::new(&qwerty);
+        let corpus = [
+            ("password", "pasword"),
+            ("letmein", "letmin"),
+            ("dragon", "dragn"),
+            ("monkey", "monkey"),
+        ];
+        let fitted = model.fit(&corpus);
+        assert!(fitted.priors().omission > model.priors().omission);
+        assert!(fitted.priors().substitution < model.priors().substitution);
+        assert_eq!(model.fit(&[]).priors(), model.priors());
+    }
+}
@@ -0,0 +1,151 @@
+//! Keyboard walks as shapes independent of where they start.
+//!
+//! "qwe", "asd" and "zxc" trace the same shape, three keys to the right, from
+//! different keys. A walk is kept as the key it starts on and the direction
+//! of each step, so walks can be grouped by shape and a shape traced on 
+//! another layout to see what the same hand movement types there.
+
+use std::collections::HashMap;
+use geometry;
+use ::*;
+
+/// A keyboard walk
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Walk {
+    /// The first key pressed
+    pub start: Key,
+    /// Position of each key relative to the one before
+    pub directions: Vec<Edge>,
+    /// Whether each key, including the first, is pressed with shift
+    pub shifted: Vec<bool>,
+}
+
+/// Encodes text as a walk. Returns None if the text is empty, a character 
+/// isn't on the keyboard or a key isn't next to the one before it. Another
+/// layer of the same key doesn't count as next to it.
+pub fn encode(keyboard: &Keyboard, text: &str) -> Option<Walk> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let keys = chars.iter()
+                    .map(|c| keyboard.find_key(*c))
+                    .collect::<Option<Vec<_>>>()?;
+    let start = *keys.first()?;
+    let directions = keys.windows(2)
+                         .map(|w| keyboard.edge_weight(w[0], w[1])
+                                          .cloned()
+                                          .filter(|e| !e.is_same_key()))
+                         .collect::<Option<Vec<_>>>()?;
+    let shifted = keys.iter().zip(chars.iter()).map(|(k, c)| k.value != *c).collect();
+    Some(Walk {
+        start,
+        directions,
+        shifted,
+    })
+}
+
+/// Types a walk, following its directions from the start key. Returns None
+/// if the walk runs off the keyboard.
+pub fn render(keyboard: &Keyboard, walk: &Walk) -> Option<String> {
+    keyboard.walk(walk.start, &walk.directions, &walk.shifted)
+}
+
+/// Types the shape of text walked on one keyboard on another, starting from 
+/// the key in the same place. Returns None if the text isn't a walk or the 
+/// shape runs off the other keyboard.
+pub fn render_on(from: &Keyboard, to: &Keyboard, text: &str) -> Option<String> {
+    let walk = from.encode_walk(text)?;
+    let position = *geometry::grid_positions(from).get(&walk.start)?;
+    let layer = from.find_layer(walk.start);
+    let start = geometry::grid_positions(to).into_iter()
+                                            .find(|&(k, p)| p == position && to.find_layer(k) == layer)
+                                            .map(|(k, _)| k)?;
+    render(to, &Walk { start, ..walk })
+}
+
+/// Returns true if both texts are walks with the same shape
+pub fn same_shape(keyboard: &Keyboard, a: &str, b: &str) -> bool {
+    match (keyboard.encode_walk(a), keyboard.encode_walk(b)) {
+        (Some(a), Some(b)) => a.directions == b.directions,
+        _ => false,
+    }
+}
+
+/// Groups texts by the shape of their walk, in the order each shape is first
+/// seen. Texts which aren't walks are left out.
+pub fn cluster<'t>(keyboard: &Keyboard, texts: &[&'t str]) -> Vec<Vec<&'t str>> {
+    let mut groups: Vec<Vec<&str>> = Vec::new();
+    let mut shapes = HashMap::new();
+    for text in texts {
+        if let Some(walk) = keyboard.encode_walk(text) {
+            let index = *shapes.entry(walk.directions).or_insert(groups.len());
+            if index == groups.len() {
+                groups.push(Vec::new());
+            }
+            groups[index].push(text);
+        }
+    }
+    groups
+}
+
+
+#[cfg(test)]
+mod tests {
+    use walk::*;
+
+    #[test]
+    fn test_encode() {
+        let qwerty = generate_qwerty_us();
+        let walk = encode(&qwerty, "qwE").unwrap();
+        let right = Edge { horizontal: Direction::Next, vertical: Direction::Same };
+        assert_eq!(walk.start, qwerty.find_key('q').unwrap());
+        assert_eq!(walk.directions, vec![right, right]);
+        assert_eq!(walk.shifted, vec![false, false, true]);
+        assert_eq!(render(&qwerty, &walk), Some("qwE".to_string()));
+
+        assert!(encode(&qwerty, "qe").is_none());
+        assert!(encode(&qwerty, "").is_none());
+        assert_eq!(encode(&qwerty, "g").unwrap().directions, vec![]);
+
+        // Runs off the end of the row
+        let walk = Walk { start: qwerty.find_key('o').unwrap(), ..walk };
+        assert_eq!(render(&qwerty, &walk), Some("op{".to_string()));
+        let walk = Walk { start: qwerty.find_key('l').unwrap(), ..walk };
+        assert_eq!(render(&qwerty, &walk), Some("l;\"".to_string()));
+        let walk = Walk { start: qwerty.find_key('\'').unwrap(), ..walk };
+        assert_eq!(render(&qwerty, &walk), None);
+
+        // Keys typing a cluster type all of it
+        let inscript = generate_inscript();
+        let seven = inscript.find_key('7').unwrap();
+        let right = Edge { horizontal: Direction::Next, vertical: Direction::Same };
+        assert_eq!(inscript.walk(seven, &[right], &[true, true]), Some("क्षश्र".to_string()));
+    }
+
+    #[test]
+    fn test_shapes() {
+        let qwerty = generate_qwerty_us();
+        assert!(same_shape(&qwerty, "qwe", "zxc"));
+        assert!(same_shape(&qwerty, "qaz", "wsx"));
+        assert!(!same_shape(&qwerty, "qwe", "qaz"));
+        assert!(!same_shape(&qwerty, "qwe", "qwer"));
+
+        let texts = ["qwe", "1qaz", "asd", "2wsx", "hello", "zxc"];
+        assert_eq!(cluster(&qwerty, &texts),
+                   vec![vec!["qwe", "asd", "zxc"], vec!["1qaz", "2wsx"]]);
+    }
+
+    #[test]
+    fn test_render_on() {
+        let qwerty = generate_qwerty_us();
+        let dvorak = generate_dvorak();
+        assert_eq!(render_on(&qwerty, &dvorak, "asdf"), Some("aoeu".to_string()));
+        assert_eq!(render_on(&qwerty, &dvorak, "1qaz"), Some("1'a;".to_string()));
+        assert_eq!(render_on(&qwerty, &dvorak, "QWE"), Some("\"<>".to_string()));
+        assert_eq!(render_on(&qwerty, &generate_russian(), "qwerty"), Some("йцукен".to_string()));
+        assert_eq!(render_on(&qwerty, &dvorak, "hello"), None);
+
+        // JIS has a void key left of 1 but the shapes start in the same place
+        let jis = generate_jis();
+        assert_eq!(render_on(&qwerty, &jis, "qwe"), Some("qwe".to_string()));
+        assert_eq!(render_on(&jis, &qwerty, "1qaz"), Some("1qaz".to_string()));
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::iter_nth_zero, clippy::iter_count)]
+
 extern crate keygraph_rs;
 extern crate petgraph;
 
@@ -12,7 +14,15 @@ fn test_keyboard_contents() {
 
     let relevant_keyboards = vec![
         generate_qwerty_us(),
-        generate_dvorak()
+        generate_dvorak(),
+        generate_programmer_dvorak(),
+        generate_dvorak_uk(),
+        generate_dvorak_left(),
+        generate_dvorak_right(),
+        generate_colemak(),
+        generate_colemak_dh(),
+        generate_colemak_dh_iso(),
+        generate_workman()
     ];
     for keyboard in relevant_keyboards.iter() {
         for c in characters.chars() {
@@ -25,8 +35,8 @@ fn test_keyboard_contents() {
         for c in alphabet.chars() {
             let key = keyboard.find_key(c);
             let made_key = Key {
-                value: c,
-                shifted: c.to_uppercase().nth(0).unwrap()
+                value: c.into(),
+                shifted: c.to_uppercase().nth(0).unwrap().into()
             };
 
             assert!(key.is_some());
@@ -225,3 +235,839 @@ fn test_dvorak() {
     test_neighbours(expected, actual);
 
 }
+
+#[test]
+fn test_dvorak_variants() {
+    let programmer = generate_programmer_dvorak();
+    // testing '7' which is the shifted value of '['
+    let reference_key = programmer.find_key('7');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+    assert!(reference_key.is_shifted('7'));
+    assert!(reference_key.is_unshifted('['));
+
+    let expected = vec![
+        programmer.find_key('&').unwrap(),
+        programmer.find_key('{').unwrap(),
+        programmer.find_key(';').unwrap(),
+        programmer.find_key(',').unwrap()
+    ];
+
+    let actual = programmer.neighbors_directed(reference_key, Direction::Incoming)
+                           .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+
+    let left = generate_dvorak_left();
+    // testing 'e'
+    let reference_key = left.find_key('e');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        left.find_key('h').unwrap(),
+        left.find_key('a').unwrap(),
+        left.find_key('s').unwrap(),
+        left.find_key('o').unwrap(),
+        left.find_key('n').unwrap(),
+        left.find_key('i').unwrap()
+    ];
+
+    let actual = left.neighbors_directed(reference_key, Direction::Incoming)
+                     .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+
+    let right = generate_dvorak_right();
+    // testing '9' in the bottom left corner
+    let reference_key = right.find_key('9');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        right.find_key('0').unwrap(),
+        right.find_key('7').unwrap(),
+        right.find_key('8').unwrap()
+    ];
+
+    let actual = right.neighbors_directed(reference_key, Direction::Incoming)
+                      .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+
+    let uk = generate_dvorak_uk();
+    assert_eq!(uk.find_key('£').unwrap(), uk.find_key('3').unwrap());
+    // testing '\\' on the ISO key
+    let reference_key = uk.find_key('|');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        uk.find_key(';').unwrap(),
+        uk.find_key('a').unwrap()
+    ];
+
+    let actual = uk.neighbors_directed(reference_key, Direction::Incoming)
+                   .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_colemak() {
+    let colemak = generate_colemak();
+    // testing 'd' which sits where g is on qwerty
+    let reference_key = colemak.find_key('d');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        colemak.find_key('t').unwrap(),
+        colemak.find_key('h').unwrap(),
+        colemak.find_key('g').unwrap(),
+        colemak.find_key('j').unwrap(),
+        colemak.find_key('v').unwrap(),
+        colemak.find_key('b').unwrap()
+    ];
+
+    let actual = colemak.neighbors_directed(reference_key, Direction::Incoming)
+                        .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_colemak_dh() {
+    let colemak_dh = generate_colemak_dh();
+    // testing 'd' which has moved down to where c is on qwerty
+    let reference_key = colemak_dh.find_key('d');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        colemak_dh.find_key('c').unwrap(),
+        colemak_dh.find_key('v').unwrap(),
+        colemak_dh.find_key('s').unwrap(),
+        colemak_dh.find_key('t').unwrap()
+    ];
+
+    let actual = colemak_dh.neighbors_directed(reference_key, Direction::Incoming)
+                           .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+
+    let colemak_dh_iso = generate_colemak_dh_iso();
+    // testing 'z' which is on the extra ISO key
+    let reference_key = colemak_dh_iso.find_key('z');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        colemak_dh_iso.find_key('x').unwrap(),
+        colemak_dh_iso.find_key('a').unwrap()
+    ];
+
+    let actual = colemak_dh_iso.neighbors_directed(reference_key, Direction::Incoming)
+                               .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_workman() {
+    let workman = generate_workman();
+    // testing 'h' which sits where d is on qwerty
+    let reference_key = workman.find_key('h');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        workman.find_key('s').unwrap(),
+        workman.find_key('t').unwrap(),
+        workman.find_key('r').unwrap(),
+        workman.find_key('w').unwrap(),
+        workman.find_key('x').unwrap(),
+        workman.find_key('m').unwrap()
+    ];
+
+    let actual = workman.neighbors_directed(reference_key, Direction::Incoming)
+                        .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_cyrillic() {
+    let russian = generate_russian();
+    for c in "йцукенгшщзхъфывапролджэячсмитьбюё".chars() {
+        let key = russian.find_key(c);
+        assert!(key.is_some());
+        let key = key.unwrap();
+        assert_eq!(key.shifted, c.to_uppercase().next().unwrap());
+    }
+    // testing 'к'
+    let reference_key = russian.find_key('к');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        russian.find_key('у').unwrap(),
+        russian.find_key('е').unwrap(),
+        russian.find_key('4').unwrap(),
+        russian.find_key('5').unwrap(),
+        russian.find_key('а').unwrap(),
+        russian.find_key('в').unwrap()
+    ];
+
+    let actual = russian.neighbors_directed(reference_key, Direction::Incoming)
+                        .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+    assert_eq!(russian.find_key('№'), russian.find_key('3'));
+    assert_eq!(russian.find_key(','), russian.find_key('.'));
+
+    let ukrainian = generate_ukrainian();
+    // testing 'і'
+    let reference_key = ukrainian.find_key('І');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        ukrainian.find_key('ф').unwrap(),
+        ukrainian.find_key('в').unwrap(),
+        ukrainian.find_key('ц').unwrap(),
+        ukrainian.find_key('у').unwrap(),
+        ukrainian.find_key('ч').unwrap(),
+        ukrainian.find_key('я').unwrap()
+    ];
+
+    let actual = ukrainian.neighbors_directed(reference_key, Direction::Incoming)
+                          .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+    assert!(ukrainian.find_key('Ґ').is_some());
+    assert!(ukrainian.find_key('ы').is_none());
+
+    let bulgarian = generate_bulgarian_phonetic();
+    // testing 'ъ' which is where y is on qwerty
+    let reference_key = bulgarian.find_key('ъ');
+    assert!(reference_key.is_some());
+    let reference_key = reference_key.unwrap();
+
+    let expected = vec![
+        bulgarian.find_key('т').unwrap(),
+        bulgarian.find_key('у').unwrap(),
+        bulgarian.find_key('6').unwrap(),
+        bulgarian.find_key('7').unwrap(),
+        bulgarian.find_key('х').unwrap(),
+        bulgarian.find_key('г').unwrap()
+    ];
+
+    let actual = bulgarian.neighbors_directed(reference_key, Direction::Incoming)
+                          .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_greek() {
+    let greek = generate_greek();
+    let sigma = greek.find_key('Σ');
+    assert_eq!(sigma, Some(Key::new('σ', 'Σ')));
+    let final_sigma = greek.find_key('ς');
+    assert_eq!(final_sigma, Some(Key::new('ς', '΅')));
+    // testing 'ς' which is where w is on qwerty
+    let reference_key = final_sigma.unwrap();
+
+    let expected = vec![
+        greek.find_key(';').unwrap(),
+        greek.find_key('ε').unwrap(),
+        greek.find_key('2').unwrap(),
+        greek.find_key('3').unwrap(),
+        greek.find_key('σ').unwrap(),
+        greek.find_key('α').unwrap()
+    ];
+
+    let actual = greek.neighbors_directed(reference_key, Direction::Incoming)
+                      .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_right_to_left() {
+    let hebrew = generate_hebrew();
+    assert_eq!(hebrew.find_key('ש'), Some(Key::new('ש', 'A')));
+    assert_eq!(hebrew.find_key(')'), hebrew.find_key('9'));
+    // Walks are adjacent whichever direction the text is read in
+    for walk in &["שדגכ", "כגדש"] {
+        let keys = hebrew.find_keys(walk);
+        assert!(keys.iter().all(|k| k.is_some()));
+        for pair in keys.windows(2) {
+            assert!(hebrew.contains_edge(pair[0].unwrap(), pair[1].unwrap()));
+        }
+    }
+    // Direction marks aren't keys
+    let keys = hebrew.find_keys("\u{200F}קר");
+    assert_eq!(keys.len(), 2);
+    assert!(keys.iter().all(|k| k.is_some()));
+
+    let arabic = generate_arabic();
+    let reference_key = arabic.find_key('ب');
+    assert_eq!(reference_key, Some(Key::new('ب', '[')));
+    let reference_key = reference_key.unwrap();
+    assert_eq!(arabic.find_key('\u{064B}'), arabic.find_key('ص'));
+
+    let expected = vec![
+        arabic.find_key('ي').unwrap(),
+        arabic.find_key('ل').unwrap(),
+        arabic.find_key('ق').unwrap(),
+        arabic.find_key('ف').unwrap(),
+        arabic.find_key('ر').unwrap(),
+        arabic.find_key('ؤ').unwrap()
+    ];
+
+    let actual = arabic.neighbors_directed(reference_key, Direction::Incoming)
+                       .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+
+    // Shaped forms of شسي (a walk along the home row) and a final lam-alef
+    let keys = arabic.find_keys("\u{FEB7}\u{FEB4}\u{FEF2}\u{FEFC}");
+    assert_eq!(keys, vec![
+        arabic.find_key('ش'),
+        arabic.find_key('س'),
+        arabic.find_key('ي'),
+        arabic.find_key('\u{FEFB}')
+    ]);
+    assert!(keys.iter().all(|k| k.is_some()));
+}
+
+#[test]
+fn test_jis() {
+    let jis = generate_jis();
+    assert_eq!(jis.find_key('¥'), Some(Key::new('¥', '|')));
+    assert_eq!(jis.find_key('_'), Some(Key::new('\\', '_')));
+    assert_eq!(jis.find_key('@'), Some(Key::new('@', '`')));
+
+    let ka = jis.find_key('か');
+    assert!(ka.is_some());
+    let ka = ka.unwrap();
+    let t = jis.find_key('t').unwrap();
+    assert_eq!(jis.find_alternates(ka), vec![t]);
+    assert_eq!(jis.find_alternates(t), vec![ka]);
+    assert!(jis.find_alternates(jis.find_key('0').unwrap()).contains(&jis.find_key('を').unwrap()));
+
+    // Kana have the same neighbours as the latin keys they share
+    let expected = vec![
+        jis.find_key('す').unwrap(),
+        jis.find_key('ん').unwrap(),
+        jis.find_key('え').unwrap(),
+        jis.find_key('お').unwrap(),
+        jis.find_key('き').unwrap(),
+        jis.find_key('は').unwrap(),
+        t
+    ];
+
+    let actual = jis.neighbors_directed(ka, Direction::Incoming)
+                    .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+
+    // Voiced kana and katakana are typed with the dakuten key
+    let keys = jis.find_keys("ガギ");
+    assert_eq!(keys, vec![
+        Some(ka),
+        jis.find_key('゛'),
+        jis.find_key('き'),
+        jis.find_key('゛')
+    ]);
+    assert!(keys.iter().all(|k| k.is_some()));
+    assert_eq!(jis.find_alternates(keys[1].unwrap()), vec![jis.find_key('@').unwrap()]);
+}
+
+#[test]
+fn test_dubeolsik() {
+    let dubeolsik = generate_dubeolsik();
+    assert_eq!(dubeolsik.find_key('ㅃ'), Some(Key::new('ㅂ', 'ㅃ')));
+    // Syllables can't be found on their own
+    assert!(dubeolsik.find_key('한').is_none());
+
+    let keys = dubeolsik.find_keys("한국");
+    assert_eq!(keys, vec![
+        dubeolsik.find_key('ㅎ'),
+        dubeolsik.find_key('ㅏ'),
+        dubeolsik.find_key('ㄴ'),
+        dubeolsik.find_key('ㄱ'),
+        dubeolsik.find_key('ㅜ'),
+        dubeolsik.find_key('ㄱ')
+    ]);
+    assert!(keys.iter().all(|k| k.is_some()));
+
+    // A walk along the top row
+    let keys = dubeolsik.find_keys("ㅂㅈㄷㄱ");
+    for pair in keys.windows(2) {
+        assert!(dubeolsik.contains_edge(pair[0].unwrap(), pair[1].unwrap()));
+    }
+
+    // testing 'ㅇ' which is where d is on qwerty
+    let reference_key = dubeolsik.find_key('ㅇ').unwrap();
+
+    let expected = vec![
+        dubeolsik.find_key('ㄴ').unwrap(),
+        dubeolsik.find_key('ㄹ').unwrap(),
+        dubeolsik.find_key('ㄷ').unwrap(),
+        dubeolsik.find_key('ㄱ').unwrap(),
+        dubeolsik.find_key('ㅌ').unwrap(),
+        dubeolsik.find_key('ㅊ').unwrap()
+    ];
+
+    let actual = dubeolsik.neighbors_directed(reference_key, Direction::Incoming)
+                          .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_inscript() {
+    let inscript = generate_inscript();
+    let seven = inscript.find_key('7').unwrap();
+    assert_eq!(seven.value_output(), "7");
+    assert_eq!(seven.shifted_output(), "क्ष");
+    assert_eq!(seven.shifted.as_str(), "क्ष");
+    // Keys typing a cluster aren't found by a single character of it
+    assert_eq!(inscript.find_key('\u{E004}'), None);
+    assert_eq!(inscript.find_key('क'), Some(Key::new('क', 'ख')));
+
+    // क्ष is typed with one key, the vowel sign in त्रि follows the conjunct
+    let clusters = inscript.find_clusters("क्षत्रिय");
+    assert_eq!(clusters, vec![
+        Some(vec![seven]),
+        Some(vec![inscript.find_key('6').unwrap(), inscript.find_key('ि').unwrap()]),
+        Some(vec![inscript.find_key('य').unwrap()])
+    ]);
+
+    // Conjuncts without their own key are typed with the virama
+    let clusters = inscript.find_clusters("क्क");
+    assert_eq!(clusters, vec![
+        Some(vec![
+            inscript.find_key('क').unwrap(),
+            inscript.find_key('्').unwrap(),
+            inscript.find_key('क').unwrap()
+        ])
+    ]);
+    assert_eq!(inscript.find_clusters("कx"), vec![
+        Some(vec![inscript.find_key('क').unwrap()]),
+        None
+    ]);
+
+    // testing 'क' which is where k is on qwerty
+    let reference_key = inscript.find_key('ख').unwrap();
+    assert!(reference_key.is_unshifted('क'));
+
+    let expected = vec![
+        inscript.find_key('र').unwrap(),
+        inscript.find_key('त').unwrap(),
+        inscript.find_key('ग').unwrap(),
+        inscript.find_key('द').unwrap(),
+        inscript.find_key(',').unwrap(),
+        inscript.find_key('स').unwrap()
+    ];
+
+    let actual = inscript.neighbors_directed(reference_key, Direction::Incoming)
+                         .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_kedmanee() {
+    let kedmanee = generate_kedmanee();
+    assert_eq!(kedmanee.find_key('฿'), Some(Key::new('ึ', '฿')));
+
+    // A consonant with a vowel and tone mark is one cluster of three keys
+    let clusters = kedmanee.find_clusters("กี่ไก่");
+    assert_eq!(clusters.len(), 3);
+    assert_eq!(clusters[0], Some(vec![
+        kedmanee.find_key('ก').unwrap(),
+        kedmanee.find_key('ี').unwrap(),
+        kedmanee.find_key('่').unwrap()
+    ]));
+    assert_eq!(kedmanee.find_keys("กี่ไก่").len(), 6);
+
+    // testing 'ก' which is where d is on qwerty
+    let reference_key = kedmanee.find_key('ก').unwrap();
+
+    let expected = vec![
+        kedmanee.find_key('ห').unwrap(),
+        kedmanee.find_key('ด').unwrap(),
+        kedmanee.find_key('ำ').unwrap(),
+        kedmanee.find_key('พ').unwrap(),
+        kedmanee.find_key('แ').unwrap(),
+        kedmanee.find_key('ป').unwrap()
+    ];
+
+    let actual = kedmanee.neighbors_directed(reference_key, Direction::Incoming)
+                         .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_zhuyin() {
+    let zhuyin = generate_zhuyin();
+    let b = zhuyin.find_key('ㄅ');
+    assert_eq!(b, Some(Key::new('ㄅ', '\0')));
+    let b = b.unwrap();
+    assert_eq!(zhuyin.find_alternates(b), vec![Key::new('1', '!')]);
+    // Keys without a symbol only have latin characters
+    assert!(zhuyin.find_alternates(zhuyin.find_key('[').unwrap()).is_empty());
+
+    let expected = vec![
+        zhuyin.find_key('`').unwrap(),
+        zhuyin.find_key('ㄉ').unwrap(),
+        zhuyin.find_key('ㄆ').unwrap()
+    ];
+
+    let actual = zhuyin.neighbors_directed(b, Direction::Incoming)
+                       .collect::<Vec<_>>();
+
+    test_neighbours(expected, actual);
+
+    // A walk along the number row maps back to the latin keys typed
+    let latin = zhuyin.find_keys("ㄅㄉˇˋ")
+                      .into_iter()
+                      .map(|k| zhuyin.find_alternates(k.unwrap())[0].value_output())
+                      .collect::<String>();
+    assert_eq!(latin, "1234");
+    assert_eq!(zhuyin.find_alternates(zhuyin.find_key('Z').unwrap()),
+               vec![zhuyin.find_key('ㄈ').unwrap()]);
+}
+
+#[test]
+fn test_nordic() {
+    let swedish = generate_swedish();
+    let one = swedish.find_key('1').unwrap();
+    let section = swedish.find_key('§').unwrap();
+    assert_eq!(section, Key::new('§', '½'));
+    assert_eq!(swedish.find_key('Ö'), Some(Key::new('ö', 'Ö')));
+    assert!(swedish.neighbors_directed(one, Direction::Incoming).any(|k| k == section));
+
+    // AltGr characters are on a layer above the key they share
+    let at = swedish.find_key('@').unwrap();
+    assert_eq!(swedish.find_alternates(at), vec![swedish.find_key('2').unwrap()]);
+    assert_eq!(swedish.find_alternates(swedish.find_key('<').unwrap()),
+               vec![swedish.find_key('|').unwrap()]);
+
+    let expected = vec![
+        swedish.find_key('l').unwrap(),
+        swedish.find_key('ä').unwrap(),
+        swedish.find_key('p').unwrap(),
+        swedish.find_key('å').unwrap(),
+        swedish.find_key('.').unwrap(),
+        swedish.find_key('-').unwrap(),
+    ];
+    let actual = swedish.neighbors_directed(swedish.find_key('ö').unwrap(), Direction::Incoming)
+                        .collect::<Vec<_>>();
+    test_neighbours(expected, actual);
+
+    // The extra ISO key is left of z
+    let z = swedish.find_key('z').unwrap();
+    assert!(swedish.neighbors_directed(z, Direction::Incoming)
+                   .any(|k| k == swedish.find_key('>').unwrap()));
+
+    let finnish = generate_finnish();
+    assert_eq!(finnish.node_count(), swedish.node_count());
+    assert_eq!(finnish.edge_count(), swedish.edge_count());
+
+    // Norwegian and danish swap æ and ø
+    let norwegian = generate_norwegian();
+    let danish = generate_danish();
+    let l = Key::new('l', 'L');
+    assert!(norwegian.neighbors_directed(l, Direction::Incoming)
+                     .any(|k| k == norwegian.find_key('ø').unwrap()));
+    assert!(danish.neighbors_directed(l, Direction::Incoming)
+                  .any(|k| k == danish.find_key('æ').unwrap()));
+    assert_eq!(danish.find_alternates(danish.find_key('<').unwrap()),
+               vec![danish.find_key('\\').unwrap()]);
+    assert_eq!(norwegian.find_key('\\'), Some(Key::new('\\', '`')));
+
+    let icelandic = generate_icelandic();
+    assert_eq!(icelandic.find_key('Þ'), Some(Key::new('þ', 'Þ')));
+    assert_eq!(icelandic.find_key('ð'), Some(Key::new('ð', 'Ð')));
+    assert_eq!(icelandic.find_alternates(icelandic.find_key('q').unwrap()),
+               vec![icelandic.find_key('@').unwrap()]);
+    assert!(icelandic.neighbors_directed(icelandic.find_key('.').unwrap(), Direction::Incoming)
+                     .any(|k| k.value == 'þ'));
+}
+
+#[test]
+fn test_southern_european() {
+    let spanish = generate_spanish();
+    assert_eq!(spanish.find_key('Ñ'), Some(Key::new('ñ', 'Ñ')));
+    assert_eq!(spanish.find_alternates(spanish.find_key('2').unwrap()),
+               vec![spanish.find_key('@').unwrap()]);
+
+    // Accented vowels are typed with the dead key first, ñ has its own key
+    let acute = spanish.find_key('´').unwrap();
+    let e = spanish.find_key('e').unwrap();
+    assert_eq!(spanish.find_keys("é"), vec![Some(acute), Some(e)]);
+    assert_eq!(spanish.find_keys("Ü"), vec![Some(acute), spanish.find_key('U')]);
+    assert_eq!(spanish.find_keys("ñ"), vec![spanish.find_key('ñ')]);
+    assert_eq!(generate_qwerty_us().find_keys("é"), vec![None]);
+
+    let latin_american = generate_latin_american();
+    assert_eq!(latin_american.find_key('¿'), Some(Key::new('¿', '¡')));
+    assert_eq!(latin_american.find_alternates(latin_american.find_key('q').unwrap()),
+               vec![latin_american.find_key('@').unwrap()]);
+
+    // Italian has keys for its accented vowels and no dead keys
+    let italian = generate_italian();
+    assert_eq!(italian.find_key('é'), Some(Key::new('è', 'é')));
+    assert_eq!(italian.find_keys("á"), vec![None]);
+    let bracket = italian.find_key('{').unwrap();
+    assert_eq!(bracket, Key::new('[', '{'));
+    assert_eq!(italian.find_alternates(bracket), vec![italian.find_key('è').unwrap()]);
+
+    let portuguese = generate_portuguese();
+    assert_eq!(portuguese.find_keys("ã"), vec![portuguese.find_key('~'), portuguese.find_key('a')]);
+    assert_eq!(portuguese.find_key('ç'), Some(Key::new('ç', 'Ç')));
+
+    // The ABNT2 extra key sits between ; and right shift
+    let abnt2 = generate_abnt2();
+    let slash = abnt2.find_key('/').unwrap();
+    assert_eq!(slash, Key::new('/', '?'));
+    let expected = vec![
+        abnt2.find_key(';').unwrap(),
+        abnt2.find_key('~').unwrap(),
+        abnt2.find_key(']').unwrap(),
+    ];
+    let actual = abnt2.neighbors_directed(slash, Direction::Incoming)
+                      .collect::<Vec<_>>();
+    test_neighbours(expected, actual);
+    assert_eq!(abnt2.find_keys("õ"), vec![abnt2.find_key('~'), abnt2.find_key('o')]);
+}
+
+#[test]
+fn test_turkish() {
+    let turkish_q = generate_turkish_q();
+    assert_eq!(turkish_q.find_key('İ'), Some(Key::new('i', 'İ')));
+    assert_eq!(turkish_q.find_key('I'), Some(Key::new('ı', 'I')));
+    assert_eq!(turkish_q.find_key('é'), Some(Key::new('"', 'é')));
+
+    // The dotted i is on the home row next to ş, the dotless ı replaces qwerty i
+    let dotted = turkish_q.find_key('i').unwrap();
+    let dotless = turkish_q.find_key('ı').unwrap();
+    assert!(turkish_q.neighbors_directed(dotted, Direction::Incoming)
+                     .any(|k| k == turkish_q.find_key('ş').unwrap()));
+    assert!(turkish_q.neighbors_directed(dotless, Direction::Incoming)
+                     .any(|k| k == turkish_q.find_key('u').unwrap()));
+    assert_eq!(turkish_q.find_alternates(turkish_q.find_key('q').unwrap()),
+               vec![turkish_q.find_key('@').unwrap()]);
+
+    let turkish_f = generate_turkish_f();
+    assert_eq!(turkish_f.find_key('I'), Some(dotless));
+    let expected = vec![
+        turkish_f.find_key('u').unwrap(),
+        turkish_f.find_key('e').unwrap(),
+        turkish_f.find_key('g').unwrap(),
+        turkish_f.find_key('ğ').unwrap(),
+        turkish_f.find_key('j').unwrap(),
+        turkish_f.find_key('ö').unwrap(),
+    ];
+    let actual = turkish_f.neighbors_directed(dotted, Direction::Incoming)
+                          .collect::<Vec<_>>();
+    test_neighbours(expected, actual);
+}
+
+#[test]
+fn test_bepo() {
+    let bepo = generate_bepo();
+    // Every non void position is a distinct key
+    assert_eq!(bepo.node_count(), 48 + 44);
+    assert_eq!(bepo.find_key('1'), Some(Key::new('"', '1')));
+    assert_eq!(bepo.find_level('é'), Some(Level { layer: 0, shifted: false }));
+    assert_eq!(bepo.find_level('É'), Some(Level { layer: 0, shifted: true }));
+    assert_eq!(bepo.find_level('œ'), Some(Level { layer: 1, shifted: false }));
+    assert_eq!(bepo.find_level('Œ'), Some(Level { layer: 1, shifted: true }));
+    assert_eq!(bepo.find_level('☃'), None);
+    assert_eq!(bepo.find_alternates(bepo.find_key('œ').unwrap()),
+               vec![bepo.find_key('o').unwrap()]);
+}
+
+#[test]
+fn test_neo2() {
+    let neo = generate_neo2();
+    assert_eq!(neo.node_count(), 46 + 42 + 42 + 17);
+
+    let levels = [
+        ('e', 0, false), ('E', 0, true),
+        ('}', 1, false), ('ε', 1, true),
+        ('∃', 2, false),
+        ('−', 3, false),
+    ];
+    for &(c, layer, shifted) in levels.iter() {
+        assert_eq!(neo.find_level(c), Some(Level { layer, shifted }));
+    }
+
+    // The numpad digits on Mod4 are separate keys to the number row
+    let number = neo.find_key('5').unwrap();
+    assert_eq!(number, Key::new('5', '«'));
+    assert_eq!(neo.find_layer(number), 0);
+    let r = neo.find_key('r').unwrap();
+    let alternates = neo.find_alternates(r);
+    assert_eq!(alternates.len(), 3);
+    assert!(alternates.contains(&Key::new('5', '\0')));
+    assert_eq!(neo.find_layer(Key::new('5', '\0')), 3);
+
+    // The numpad on Mod4 is connected like the base keys
+    let expected = vec![
+        Key::new('4', '\0'),
+        Key::new('6', '\0'),
+        Key::new('8', '\0'),
+        Key::new('9', '\0'),
+        Key::new('1', '\0'),
+        Key::new('2', '\0'),
+    ];
+    let actual = neo.neighbors_directed(Key::new('5', '\0'), Direction::Incoming)
+                    .filter(|k| !neo.edge_weight(*k, Key::new('5', '\0'))
+                                    .unwrap()
+                                    .is_same_key())
+                    .collect::<Vec<_>>();
+    test_neighbours(expected, actual);
+
+    // Layers above the base keys are found for earlier layouts too
+    assert_eq!(generate_swedish().find_level('@'), Some(Level { layer: 1, shifted: false }));
+    assert_eq!(generate_jis().find_level('ぁ'), Some(Level { layer: 1, shifted: true }));
+    assert_eq!(generate_qwerty_us().find_level('!'), Some(Level { layer: 0, shifted: true }));
+}
+
+#[test]
+fn test_mobile() {
+    use keygraph_rs::Direction::{Next, Same};
+
+    let ios = generate_ios();
+    // Rows are offset by half a key so s touches w, e, a, d, z and x
+    let s = ios.find_key('s').unwrap();
+    let expected = vec![
+        ios.find_key('w').unwrap(),
+        ios.find_key('e').unwrap(),
+        ios.find_key('a').unwrap(),
+        ios.find_key('d').unwrap(),
+        ios.find_key('z').unwrap(),
+        ios.find_key('x').unwrap(),
+    ];
+    let actual = ios.neighbors_directed(s, Direction::Incoming)
+                    .filter(|k| !ios.edge_weight(*k, s).unwrap().is_same_key())
+                    .collect::<Vec<_>>();
+    test_neighbours(expected, actual);
+
+    // Without the number row q has no key above it
+    let q = ios.find_key('q').unwrap();
+    let e = ios.find_key('e').unwrap();
+    assert!(ios.neighbors_directed(q, Direction::Incoming)
+               .all(|k| k.value == 'w' || k.value == 'a' || ios.find_layer(k) == 1));
+    assert_eq!(ios.edge_weight(ios.find_key('w').unwrap(), e),
+               Some(&Edge { horizontal: Next, vertical: Same }));
+
+    // The symbols page rows are aligned with each other
+    let five = ios.find_key('5').unwrap();
+    assert_eq!(five, Key::new('5', '#'));
+    assert_eq!(ios.find_layer(five), 1);
+    assert_eq!(ios.edge_weight(five, ios.find_key('(').unwrap()),
+               Some(&Edge { horizontal: Same, vertical: Next }));
+
+    // Long press alternates are found through the key search
+    let acute = ios.find_key('É').unwrap();
+    assert_eq!(acute, Key::new('é', 'É'));
+    assert_eq!(ios.find_alternates(acute)[0], e);
+    assert!(ios.find_alternates(e).contains(&acute));
+    assert_eq!(ios.find_keys("café"), vec![
+        ios.find_key('c'),
+        ios.find_key('a'),
+        ios.find_key('f'),
+        Some(acute),
+    ]);
+
+    let android = generate_android();
+    assert_eq!(android.find_key('π'), Some(Key::new('6', 'π')));
+    assert_eq!(android.find_level('@'), Some(Level { layer: 1, shifted: false }));
+    assert!(android.find_key('ñ').is_some());
+}
+
+#[test]
+fn test_phone_keypad() {
+    use keygraph_rs::Direction::{Next, Same};
+
+    let keypad = generate_phone_keypad();
+    assert_eq!(keypad.node_count(), 12);
+
+    // 2580 runs straight down the middle column
+    let keys = keypad.find_keys("2580");
+    assert!(keys.iter().all(|k| k.is_some()));
+    for pair in keys.windows(2) {
+        assert_eq!(keypad.edge_weight(pair[0].unwrap(), pair[1].unwrap()),
+                   Some(&Edge { horizontal: Same, vertical: Next }));
+    }
+
+    // Aligned keys have diagonal neighbours
+    let five = keypad.find_key('5').unwrap();
+    assert_eq!(keypad.neighbors_directed(five, Direction::Incoming).count(), 8);
+    assert_eq!(keypad.edge_weight(keypad.find_key('1').unwrap(), five),
+               Some(&Edge { horizontal: Next, vertical: Next }));
+    assert!(keypad.find_key('#').is_some());
+
+    let word = t9::encode("love").unwrap();
+    assert_eq!(word, "5683");
+    assert!(keypad.find_keys(&word).iter().all(|k| k.is_some()));
+}
+
+#[test]
+fn test_walk() {
+    use keygraph_rs::Direction::{Next, Previous, Same};
+
+    let qwerty = generate_qwerty_us();
+    let g = qwerty.find_key('g').unwrap();
+    let right = Edge { horizontal: Next, vertical: Same };
+    let up = Edge { horizontal: Same, vertical: Previous };
+    assert_eq!(qwerty.step(g, right), qwerty.find_key('h'));
+    assert_eq!(qwerty.step(g, up), qwerty.find_key('t'));
+    assert_eq!(qwerty.walk(g, &[right, up], &[]), Some("ghy".to_string()));
+    assert_eq!(qwerty.walk(g, &[right, up], &[true, false, true]), Some("GhY".to_string()));
+
+    let walk = qwerty.encode_walk("GhY").unwrap();
+    assert_eq!(walk.start, g);
+    assert_eq!(walk.directions, vec![right, up]);
+    assert_eq!(qwerty.walk(walk.start, &walk.directions, &walk.shifted), Some("GhY".to_string()));
+    assert!(qwerty.encode_walk("gj").is_none());
+
+    // Off the top of the keyboard
+    let one = qwerty.find_key('1').unwrap();
+    assert_eq!(qwerty.step(one, up), None);
+    assert_eq!(qwerty.walk(one, &[right, up], &[]), None);
+
+    // Layers of the same key aren't a step
+    let jis = generate_jis();
+    let t = jis.find_key('t').unwrap();
+    assert_eq!(jis.step(t, Edge { horizontal: Same, vertical: Same }), None);
+    assert!(jis.encode_walk("tか").is_none());
+    assert!(jis.encode_walk("かき").is_some());
+}
+
+#[test]
+fn test_key_search_defaults() {
+    // Implementors outside the crate only need to find single keys
+    struct Single(Key);
+    impl KeySearch for Single {
+        fn find_key(&self, v: char) -> Option<Key> {
+            if self.0.value == v || self.0.shifted == v { Some(self.0) } else { None }
+        }
+    }
+
+    let key = Key::new('a', 'A');
+    let single = Single(key);
+    assert_eq!(single.find_keys("aAb"), vec![Some(key), Some(key), None]);
+    assert_eq!(single.find_clusters("ab"), vec![Some(vec![key]), None]);
+    assert!(single.find_alternates(key).is_empty());
+    assert_eq!(single.find_level('A'), Some(Level { layer: 0, shifted: true }));
+}